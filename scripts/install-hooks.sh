@@ -47,6 +47,9 @@ HOOK_ENTRY=$(jq -nc --arg cmd "$HOOK_CMD" '[{
   }]
 }]')
 
+# The notify line the plugin adds to ~/.codex/config.toml
+CODEX_NOTIFY='^[[:space:]]*notify[[:space:]]*=.*zellaude-hook\.sh'
+
 EVENTS='["PreToolUse","PostToolUse","PostToolUseFailure","UserPromptSubmit","PermissionRequest","Notification","Stop","SubagentStop","SessionStart","SessionEnd"]'
 
 backup_settings() {
@@ -84,6 +87,25 @@ uninstall() {
   echo "Uninstalled zellaude hooks from $SETTINGS"
 }
 
+# Remove the `notify` line the plugin adds to Codex's config, leaving any
+# other notify command alone
+uninstall_codex() {
+  local config="$HOME/.codex/config.toml"
+  if [ -L "$config" ]; then
+    config="$(resolve_file_symlink "$config")"
+  fi
+  if [ ! -f "$config" ] || ! grep -qE "$CODEX_NOTIFY" "$config"; then
+    return 0
+  fi
+
+  cp "$config" "$config.bak"
+  local tmp
+  tmp=$(mktemp)
+  grep -vE "$CODEX_NOTIFY" "$config" > "$tmp" || true
+  mv "$tmp" "$config"
+  echo "Uninstalled zellaude notify command from $config"
+}
+
 install() {
   # Create settings file if it doesn't exist
   if [ ! -f "$SETTINGS" ]; then
@@ -111,6 +133,7 @@ install() {
 
 case "${1:-}" in
   --uninstall)
+    uninstall_codex
     uninstall
     ;;
   *)
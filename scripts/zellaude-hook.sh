@@ -1,26 +1,60 @@
 #!/usr/bin/env bash
-# zellaude-hook.sh — Claude Code hook → zellij pipe bridge
+# zellaude-hook.sh — agent hook → zellij pipe bridge
 # Forwards hook events to the zellaude Zellij plugin via pipe.
 #
 # Usage in ~/.claude/settings.json hooks:
 #   "command": "/path/to/zellaude-hook.sh"
+#
+# Other agents: zellaude-hook.sh --agent <name> [event JSON]
+#   codex:  JSON from Codex's `notify` program as the last argument
+#   other:  JSON on stdin with hook_event_name set to one of session_start,
+#           session_end, prompt, tool_start, tool_end, permission,
+#           notification, turn_end, subagent_end
 
 # Exit silently if not running inside Zellij
 [ -z "$ZELLIJ_SESSION_NAME" ] && exit 0
 [ -z "$ZELLIJ_PANE_ID" ] && exit 0
 
+# Nothing may reach stdout: Claude Code adds the output of some hooks
+# (SessionStart, UserPromptSubmit) to the model's context
+exec >/dev/null
+
 # Capture send-time immediately so the plugin can order events
 # that race through parallel hook subprocesses.
 TS_MS=$(jq -nc 'now * 1000 | floor')
 
-# Read hook JSON from stdin
-INPUT=$(cat)
+AGENT=claude
+if [ "${1:-}" = "--agent" ]; then
+  AGENT="${2:-}"
+  shift 2
+fi
+
+case "$AGENT" in
+  codex)
+    # Codex passes one JSON argument; map its event type onto ours
+    INPUT=$(printf '%s' "${1:-}" | jq -c '{
+      hook_event_name: ({"agent-turn-complete": "turn_end"}[.type // ""] // empty),
+      session_id: (.["thread-id"] // .["turn-id"]),
+      cwd: .cwd
+    }' 2>/dev/null)
+    ;;
+  *)
+    # Read hook JSON from stdin
+    INPUT=$(cat)
+    ;;
+esac
 
 # Extract fields with jq (required dependency)
 HOOK_EVENT=$(echo "$INPUT" | jq -r '.hook_event_name // empty')
 SESSION_ID=$(echo "$INPUT" | jq -r '.session_id // empty')
 TOOL_NAME=$(echo "$INPUT" | jq -r '.tool_name // empty')
 CWD=$(echo "$INPUT" | jq -r '.cwd // empty')
+# One-line summary of the tool input (command, file path, pattern, ...)
+TOOL_INPUT=$(echo "$INPUT" | jq -r '
+  .tool_input // empty
+  | if type == "object" then (.command // .file_path // .path // .pattern // .url // .query // .description // .prompt // empty) else . end
+  | tostring | gsub("\\s+"; " ") | .[0:200]')
+HOOK_MESSAGE=$(echo "$INPUT" | jq -r '.message // empty')
 
 [ -z "$HOOK_EVENT" ] && exit 0
 
@@ -31,30 +65,55 @@ PAYLOAD=$(jq -nc \
   --arg hook_event "$HOOK_EVENT" \
   --arg tool_name "$TOOL_NAME" \
   --arg cwd "$CWD" \
+  --arg tool_input "$TOOL_INPUT" \
+  --arg message "$HOOK_MESSAGE" \
   --arg zellij_session "$ZELLIJ_SESSION_NAME" \
   --arg term_program "${TERM_PROGRAM:-}" \
   --arg ts_ms "$TS_MS" \
+  --arg agent "$AGENT" \
   '{
+    v: 1,
     pane_id: ($pane_id | tonumber),
     session_id: $session_id,
     hook_event: $hook_event,
     tool_name: (if $tool_name == "" then null else $tool_name end),
     cwd: (if $cwd == "" then null else $cwd end),
+    tool_input: (if $tool_input == "" then null else $tool_input end),
+    message: (if $message == "" then null else $message end),
     zellij_session: $zellij_session,
     term_program: (if $term_program == "" then null else $term_program end),
-    ts_ms: ($ts_ms | tonumber)
+    ts_ms: ($ts_ms | tonumber),
+    agent: $agent
   }')
 
-# Permission request: bell + desktop notification
-if [ "$HOOK_EVENT" = "PermissionRequest" ]; then
-  printf '\a' > /dev/tty 2>/dev/null || true
+# Send to plugin (hook is already async, no need to background). The reply
+# says whether the session is muted.
+REPLY=$(zellij pipe --name "zellaude" -- "$PAYLOAD" 2>/dev/null)
 
+# Permission request: bell + desktop notification, unless the session is muted
+case "$REPLY" in
+  "ok muted"*) exit 0 ;;
+esac
+if [ "$HOOK_EVENT" = "PermissionRequest" ] || [ "$HOOK_EVENT" = "permission" ]; then
   # Read notification setting (default: Always)
-  SETTINGS_FILE="$HOME/.config/zellij/plugins/zellaude.json"
+  SETTINGS_FILE="${XDG_CONFIG_HOME:-$HOME/.config}/zellij/plugins/zellaude.json"
   NOTIFY_MODE="Always"
+  DND=off
   if [ -f "$SETTINGS_FILE" ]; then
     NOTIFY_MODE=$(jq -r '.notifications // "Always"' "$SETTINGS_FILE" 2>/dev/null)
+    # Do not disturb: snoozed, or inside the quiet hours (local time)
+    DND=$(jq -r --argjson now "$(date +%s)" --arg hm "$(date +%H:%M)" '
+      def mins: split(":") | (.[0] | tonumber) * 60 + (.[1] | tonumber);
+      if (.dnd_until // 0) > $now then "on"
+      elif (.quiet_hours // "") != "" then
+        (.quiet_hours | split("-") | map(mins)) as [$s, $e] | ($hm | mins) as $t
+        | if ($s <= $e and $t >= $s and $t < $e) or ($s > $e and ($t >= $s or $t < $e))
+          then "on" else "off" end
+      else "off" end' "$SETTINGS_FILE" 2>/dev/null)
   fi
+  [ "$DND" = "on" ] && NOTIFY_MODE="Never"
+
+  [ "$DND" = "on" ] || printf '\a' > /dev/tty 2>/dev/null || true
 
   # For "Unfocused" mode, check if the terminal app is frontmost
   SHOULD_NOTIFY=false
@@ -134,5 +193,3 @@ if [ "$HOOK_EVENT" = "PermissionRequest" ]; then
   fi
 fi
 
-# Send to plugin (hook is already async, no need to background)
-zellij pipe --name "zellaude" -- "$PAYLOAD"
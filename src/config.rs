@@ -0,0 +1,124 @@
+use crate::state::Settings;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::path::Path;
+use zellij_tile::prelude::run_command;
+
+/// Schema version written to the settings file. Bump it and add a step to
+/// `migrate` whenever a field is renamed or changes meaning.
+pub const CONFIG_VERSION: u32 = 2;
+
+const CONFIG_FILE: &str = "zellaude.json";
+/// The settings directory as seen by the plugin once the host folder points at it
+const HOST_DIR: &str = "/host";
+
+/// Ask the host for the settings directory, creating it if needed.
+/// Honours `XDG_CONFIG_HOME`. Result arrives as a `config_dir` command result.
+pub fn resolve_dir() {
+    let mut ctx = BTreeMap::new();
+    ctx.insert("type".into(), "config_dir".into());
+    run_command(
+        &[
+            "sh",
+            "-c",
+            "d=\"${XDG_CONFIG_HOME:-$HOME/.config}/zellij/plugins\"; mkdir -p \"$d\" && printf '%s' \"$d\"",
+        ],
+        ctx,
+    );
+}
+
+/// Read the settings file through the host folder. A missing file reads as `{}`.
+pub fn read_host() -> Result<String, String> {
+    match std::fs::read_to_string(Path::new(HOST_DIR).join(CONFIG_FILE)) {
+        Ok(raw) => Ok(raw),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok("{}".into()),
+        Err(err) => Err(err.to_string()),
+    }
+}
+
+/// Write the settings file through the host folder: write a temp file unique
+/// to this instance, then rename it over the old one so readers (and other
+/// instances saving at the same time) never see a partial file.
+pub fn write_host(json: &str, instance: u32) -> Result<(), String> {
+    let dir = Path::new(HOST_DIR);
+    let tmp = dir.join(format!(".{CONFIG_FILE}.{instance}.tmp"));
+    std::fs::write(&tmp, json)
+        .and_then(|_| std::fs::rename(&tmp, dir.join(CONFIG_FILE)))
+        .map_err(|err| err.to_string())
+}
+
+/// Fallback read when the host folder can't be changed. Result arrives as a
+/// `load_config` command result.
+pub fn read_shell(dir: &str) {
+    let mut ctx = BTreeMap::new();
+    ctx.insert("type".into(), "load_config".into());
+    run_command(
+        &[
+            "sh",
+            "-c",
+            "cat \"$1/zellaude.json\" 2>/dev/null || echo '{}'",
+            "sh",
+            dir,
+        ],
+        ctx,
+    );
+}
+
+/// Fallback atomic write when the host folder can't be changed. The JSON is
+/// passed as an argument, so it needs no quoting.
+pub fn write_shell(dir: &str, json: &str) {
+    let mut ctx = BTreeMap::new();
+    ctx.insert("type".into(), "save_config".into());
+    run_command(
+        &[
+            "sh",
+            "-c",
+            "tmp=\"$1/.zellaude.json.$$.tmp\" && printf '%s' \"$2\" > \"$tmp\" && mv -f \"$tmp\" \"$1/zellaude.json\"",
+            "sh",
+            dir,
+            json,
+        ],
+        ctx,
+    );
+}
+
+/// Parse the settings file, migrating older schema versions. Also returns the
+/// version the file was written with, so a newer one isn't overwritten.
+pub fn parse(raw: &str) -> Result<(Settings, u32), String> {
+    let value: Value = serde_json::from_str(raw).map_err(|err| err.to_string())?;
+    let version = file_version(&value);
+    let value = migrate(value)?;
+    let settings = serde_json::from_value(value).map_err(|err| err.to_string())?;
+    Ok((settings, version))
+}
+
+/// Version recorded in a settings file (files from before versioning are 1).
+/// Out-of-range values read as the highest version, i.e. too new to touch.
+fn file_version(value: &Value) -> u32 {
+    value
+        .get("version")
+        .and_then(Value::as_u64)
+        .map_or(1, |v| u32::try_from(v).unwrap_or(u32::MAX))
+}
+
+fn migrate(mut value: Value) -> Result<Value, String> {
+    if !value.is_object() {
+        return Err("settings file must contain a JSON object".into());
+    }
+    let mut version = file_version(&value);
+    while version < CONFIG_VERSION {
+        value = match version {
+            1 => migrate_v1(value),
+            // The file is hand-editable, so this is user input
+            _ => return Err(format!("unsupported settings version {version}")),
+        };
+        version += 1;
+        value["version"] = version.into();
+    }
+    Ok(value)
+}
+
+/// v1 had no `version` field; every field added since has a default.
+fn migrate_v1(value: Value) -> Value {
+    value
+}
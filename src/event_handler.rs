@@ -1,4 +1,47 @@
-use crate::state::{Activity, FlashMode, HookPayload, SessionInfo, State};
+use crate::state::{
+    Activity, AgentEvent, FlashMode, HookPayload, PermissionRequest, SessionInfo, State,
+};
+
+/// Version of the hook payload format this plugin speaks. Bump it when a
+/// field is renamed or changes meaning (new optional fields don't need it).
+pub const PROTOCOL_VERSION: u32 = 1;
+
+/// Directories remembered for the launcher
+const RECENT_CWDS: usize = 8;
+
+/// Parse a hook payload. Older payloads are read as they are, since every
+/// field added since is optional; newer ones are accepted when they still
+/// parse, with `compat_note` explaining the mismatch.
+pub fn parse_payload(raw: &str) -> Result<HookPayload, String> {
+    let value: serde_json::Value =
+        serde_json::from_str(raw).map_err(|err| format!("invalid JSON: {err}"))?;
+    let version = match value.get("v") {
+        None => 0,
+        Some(v) => v
+            .as_u64()
+            .map(|v| v as u32)
+            .ok_or_else(|| format!("invalid protocol version: {v}"))?,
+    };
+    serde_json::from_value(value).map_err(|err| match compat_note(version) {
+        Some(note) => format!("{note} ({err})"),
+        None => err.to_string(),
+    })
+}
+
+/// What's off when a hook of `version` talks to this plugin, if anything.
+pub fn compat_note(version: u32) -> Option<String> {
+    if version > PROTOCOL_VERSION {
+        Some(format!(
+            "hook protocol v{version} is newer than the plugin's v{PROTOCOL_VERSION}; update the plugin"
+        ))
+    } else if version < PROTOCOL_VERSION {
+        Some(format!(
+            "hook protocol v{version} is older than the plugin's v{PROTOCOL_VERSION}; reload the plugin to update the hook"
+        ))
+    } else {
+        None
+    }
+}
 
 pub fn handle_hook_event(state: &mut State, payload: HookPayload) {
     // Capture env info for use in notifications
@@ -9,11 +52,12 @@ pub fn handle_hook_event(state: &mut State, payload: HookPayload) {
         state.term_program = Some(tp.clone());
     }
 
-    let event = payload.hook_event.as_str();
+    let event = AgentEvent::parse(&payload.hook_event);
 
     // SessionEnd → remove session (never drop: terminal cleanup)
-    if event == "SessionEnd" {
+    if event == Some(AgentEvent::SessionEnd) {
         state.sessions.remove(&payload.pane_id);
+        state.permission_queue.retain(|r| r.pane_id != payload.pane_id);
         return;
     }
 
@@ -28,29 +72,40 @@ pub fn handle_hook_event(state: &mut State, payload: HookPayload) {
         }
     }
 
+    if let Some(ref cwd) = payload.cwd {
+        state.recent_cwds.retain(|c| c != cwd);
+        state.recent_cwds.insert(0, cwd.clone());
+        state.recent_cwds.truncate(RECENT_CWDS);
+    }
+
     let activity = match event {
-        "SessionStart" => Activity::Init,
-        "PreToolUse" => {
+        Some(AgentEvent::SessionStart) => Activity::Init,
+        Some(AgentEvent::ToolStart) => {
             Activity::Tool(payload.tool_name.clone().unwrap_or_default())
         }
-        "PostToolUse" | "PostToolUseFailure" => Activity::Thinking,
-        "UserPromptSubmit" => Activity::Thinking,
-        "PermissionRequest" => Activity::Waiting,
+        Some(AgentEvent::ToolEnd) => Activity::Thinking,
+        Some(AgentEvent::Prompt) => Activity::Thinking,
+        Some(AgentEvent::Permission) => Activity::Waiting,
         // Notification is informational — just refresh the timestamp, keep current activity.
-        "Notification" => {
+        Some(AgentEvent::Notification) => {
             if let Some(session) = state.sessions.get_mut(&payload.pane_id) {
                 session.last_event_ts = crate::state::unix_now();
+                session.stalled = false;
+                if payload.message.is_some() {
+                    session.last_message = payload.message;
+                }
                 if let Some(ts_ms) = payload.ts_ms {
                     session.last_ts_ms = ts_ms;
                 }
             }
             return;
         }
-        "Stop" => Activity::Done,
-        "SubagentStop" => Activity::AgentDone,
-        _ => Activity::Idle,
+        Some(AgentEvent::TurnEnd) => Activity::Done,
+        Some(AgentEvent::SubagentEnd) => Activity::AgentDone,
+        Some(AgentEvent::SessionEnd) | None => Activity::Idle,
     };
 
+    let focused = state.focused_pane_id();
     let (tab_index, tab_name) = state
         .pane_to_tab
         .get(&payload.pane_id)
@@ -69,14 +124,32 @@ pub fn handle_hook_event(state: &mut State, payload: HookPayload) {
             last_event_ts: 0,
             cwd: None,
             last_ts_ms: 0,
+            tool_input: None,
+            last_message: None,
+            stalled: false,
+            agent: crate::state::default_agent(),
+            muted: false,
+            unseen: false,
         });
 
-    if matches!(activity, Activity::Waiting) {
+    let dnd = state
+        .settings
+        .dnd_active(crate::state::unix_now(), state.utc_offset_secs);
+    if matches!(activity, Activity::Waiting) && session.muted {
+        // Muted: the symbol still changes, but nothing flashes
+        state.flash_deadlines.remove(&payload.pane_id);
+    } else if matches!(activity, Activity::Waiting) && dnd {
+        // Held back for the summary when do-not-disturb ends
+        if !matches!(session.activity, Activity::Waiting) {
+            state.dnd_suppressed += 1;
+        }
+        state.flash_deadlines.remove(&payload.pane_id);
+    } else if matches!(activity, Activity::Waiting) {
         match state.settings.flash {
             FlashMode::Once => {
                 state.flash_deadlines.insert(
                     payload.pane_id,
-                    crate::state::unix_now_ms() + crate::state::FLASH_DURATION_MS,
+                    crate::state::unix_now_ms() + state.settings.flash_duration_ms,
                 );
             }
             FlashMode::Persist => {
@@ -90,7 +163,38 @@ pub fn handle_hook_event(state: &mut State, payload: HookPayload) {
         state.flash_deadlines.remove(&payload.pane_id);
     }
 
+    // A new request (or the end of the answered one) needs a fresh answer
+    state.answered_prompts.remove(&payload.pane_id);
+
+    // Queue new permission requests; any other event answers the pane's
+    if matches!(activity, Activity::Waiting) {
+        let request = PermissionRequest {
+            pane_id: payload.pane_id,
+            tool: payload.tool_name.clone(),
+            input: payload.tool_input.clone(),
+            ts_ms: payload.ts_ms.unwrap_or_else(crate::state::unix_now_ms),
+        };
+        match state.permission_queue.iter_mut().find(|r| r.pane_id == payload.pane_id) {
+            // Repeated while still waiting: keep its place in the queue
+            Some(queued) => {
+                queued.tool = request.tool;
+                queued.input = request.input;
+            }
+            None => state.permission_queue.push(request),
+        }
+    } else {
+        state.permission_queue.retain(|r| r.pane_id != payload.pane_id);
+    }
+
+    // Keep the tool input only while the tool is running or awaiting approval
+    session.tool_input = match activity {
+        Activity::Tool(_) | Activity::Waiting => payload.tool_input,
+        _ => None,
+    };
+    // A turn that ends out of sight stays marked until its tab is visited
+    session.unseen = matches!(activity, Activity::Done) && focused != Some(payload.pane_id);
     session.activity = activity;
+    session.stalled = false;
     session.last_event_ts = crate::state::unix_now();
     if let Some(ts_ms) = payload.ts_ms {
         session.last_ts_ms = ts_ms;
@@ -101,6 +205,9 @@ pub fn handle_hook_event(state: &mut State, payload: HookPayload) {
     if let Some(cwd) = payload.cwd {
         session.cwd = Some(cwd);
     }
+    if let Some(agent) = payload.agent {
+        session.agent = agent;
+    }
     if let Some((idx, name)) = tab_index.zip(tab_name) {
         session.tab_index = Some(idx);
         session.tab_name = Some(name);
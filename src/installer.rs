@@ -39,6 +39,22 @@ if [ -L "$SETTINGS" ]; then
   SETTINGS="$(resolve_file_symlink "$SETTINGS")"
 fi
 
+# Codex adapter: route its `notify` program through the hook, unless the
+# user already has one (Codex allows a single notify command)
+CODEX_CONFIG="$HOME/.codex/config.toml"
+if [ -L "$CODEX_CONFIG" ]; then
+  CODEX_CONFIG="$(resolve_file_symlink "$CODEX_CONFIG")"
+fi
+if [ -f "$CODEX_CONFIG" ] && ! grep -qE '^[[:space:]]*notify[[:space:]]*=' "$CODEX_CONFIG"; then
+  cp "$CODEX_CONFIG" "$CODEX_CONFIG.bak"
+  tmp=$(mktemp)
+  {
+    # Top-level key: must come before the first [table]
+    echo "notify = [\"sh\", \"-c\", 'exec \"\$HOME/.config/zellij/plugins/zellaude-hook.sh\" --agent codex \"\$1\"', \"zellaude\"]"
+    cat "$CODEX_CONFIG"
+  } > "$tmp" && mv "$tmp" "$CODEX_CONFIG"
+fi
+
 # Check if already current
 if grep -qF '__VERSION_TAG__' "$HOOK_PATH" 2>/dev/null; then
   if [ -f "$SETTINGS" ] && grep -qF "$HOOK_CMD" "$SETTINGS" 2>/dev/null; then
@@ -1,27 +1,63 @@
+mod config;
 mod event_handler;
 mod installer;
+mod notifier;
+mod overview;
+mod query;
 mod render;
 mod state;
 mod tab_pane_map;
 
-use state::{unix_now, unix_now_ms, HookPayload, MenuAction, SessionInfo, Settings, State, ViewMode};
+use state::{
+    unix_now, unix_now_ms, ClickAction, LaunchTarget, MenuAction, MenuEntry, MenuPage,
+    PermissionRequest, ScrollAction, SessionInfo, SettingKey, Settings, State, SummaryBucket,
+    SyncPayload, ViewMode,
+};
 use std::collections::BTreeMap;
 use zellij_tile::prelude::*;
 
-const DONE_TIMEOUT: u64 = 30;
+/// Shortest regular tick (elapsed counters show seconds)
 const TIMER_INTERVAL: f64 = 1.0;
+/// Longest the timer sleeps when nothing on the bar is time-dependent
+const MAX_TIMER_INTERVAL: f64 = 60.0;
 const FLASH_TICK: f64 = 0.25;
+/// Seconds between checks of the settings file for external edits
+const CONFIG_POLL_INTERVAL: u64 = 5;
+/// Two clicks on a tab within this many ms rename it
+const DOUBLE_CLICK_MS: u64 = 400;
+/// How long a tab tooltip stays after the mouse last moved over it. Zellij
+/// sends no event when the mouse leaves the pane, so it can't wait for that.
+const HOVER_TIMEOUT_MS: u64 = 2500;
+/// Keys that pick "Yes" / "No" in Claude Code's permission prompt
+const APPROVE_KEYS: &str = "1";
+const DENY_KEYS: &str = "\x1b";
+/// Focus changes remembered for back / forward
+const FOCUS_HISTORY_LEN: usize = 50;
+/// Follow mode leaves the user alone for this long after any input
+const FOLLOW_IDLE_MS: u64 = 10_000;
+/// Seconds between exchanges of activity snapshots with other Zellij sessions
+const OVERVIEW_INTERVAL: u64 = 5;
+/// Seconds to wait for a launch's new tab before dropping the command
+const LAUNCH_TIMEOUT: u64 = 5;
 
 register_plugin!(State);
 
 impl ZellijPlugin for State {
-    fn load(&mut self, _configuration: BTreeMap<String, String>) {
+    fn load(&mut self, configuration: BTreeMap<String, String>) {
+        // Settings from the layout's plugin block take precedence over the
+        // persisted file
+        self.config_overrides = configuration;
+        self.apply_config_overrides();
+        self.plugin_id = get_plugin_ids().plugin_id;
+
         request_permission(&[
             PermissionType::ReadApplicationState,
             PermissionType::ChangeApplicationState,
             PermissionType::RunCommands,
             PermissionType::ReadCliPipes,
             PermissionType::MessageAndLaunchOtherPlugins,
+            PermissionType::FullHdAccess,
+            PermissionType::WriteToStdin,
         ]);
         subscribe(&[
             EventType::TabUpdate,
@@ -31,8 +67,12 @@ impl ZellijPlugin for State {
             EventType::Mouse,
             EventType::RunCommandResult,
             EventType::PermissionRequestResult,
+            EventType::HostFolderChanged,
+            EventType::FailedToChangeHostFolder,
+            EventType::BeforeClose,
+            EventType::InputReceived,
         ]);
-        set_timeout(TIMER_INTERVAL);
+        self.schedule_tick(TIMER_INTERVAL);
 
         // Load persisted settings (may be retried in PermissionRequestResult
         // if this fires before permissions are granted)
@@ -44,19 +84,39 @@ impl ZellijPlugin for State {
             Event::TabUpdate(tabs) => {
                 let new_active = tabs.iter().find(|t| t.active).map(|t| t.position);
                 if new_active != self.active_tab_index {
-                    // Tab focus changed — clear persist flashes on the newly focused tab
+                    // Tab focus changed — clear persist flashes and unseen
+                    // completions on the newly focused tab
                     if let Some(idx) = new_active {
                         self.clear_flashes_on_tab(idx);
+                        self.mark_tab_seen(idx);
+                    }
+                    if new_active == self.jump_back {
+                        self.jump_back = None;
                     }
                 }
                 self.active_tab_index = new_active;
+                // Closed tabs leave room: start the tab list over
+                if tabs.len() < self.tabs.len() {
+                    self.tab_scroll = 0;
+                }
                 self.tabs = tabs;
                 self.rebuild_pane_map();
+                self.record_focus();
+                self.run_pending_launch();
                 true
             }
             Event::PaneUpdate(manifest) => {
+                self.is_leader = tab_pane_map::is_leader(&manifest, self.plugin_id);
                 self.pane_manifest = Some(manifest);
                 self.rebuild_pane_map();
+                self.detect_exits();
+                // Focusing a pane counts as having seen what it finished
+                if let Some(session) =
+                    self.focused_pane_id().and_then(|id| self.sessions.get_mut(&id))
+                {
+                    session.unseen = false;
+                }
+                self.record_focus();
                 true
             }
             Event::ModeUpdate(mode_info) => {
@@ -66,25 +126,142 @@ impl ZellijPlugin for State {
                 }
                 true
             }
-            Event::Mouse(Mouse::LeftClick(_, col)) => {
-                let col = col as usize;
+            Event::InputReceived => {
+                self.last_input_ms = unix_now_ms();
+                // Typing into a waiting pane answers its prompt; the session
+                // only leaves Waiting when the tool is done
+                match self.focused_pane_id() {
+                    Some(pane_id) if self.is_waiting(pane_id) => {
+                        self.answered_prompts.insert(pane_id)
+                    }
+                    _ => false,
+                }
+            }
+            Event::Mouse(Mouse::RightClick(line, col)) => {
+                // Zellij doesn't pass middle clicks to plugins, so the
+                // configurable tab action is on the right button
+                let row = line.max(0) as usize;
+                if self.view_mode != ViewMode::Normal {
+                    return false;
+                }
+                if self.settings.right_click == ClickAction::FocusUrgent {
+                    self.focus_urgent();
+                    return false;
+                }
+                let Some(region) = self
+                    .click_regions
+                    .iter()
+                    .find(|r| r.row == row && col >= r.start_col && col < r.end_col)
+                else {
+                    return false;
+                };
+                let (tab, pane_id) = (region.tab_index, region.pane_id);
+                if self.settings.right_click == ClickAction::CloseTab {
+                    close_tab_with_index(tab);
+                    return false;
+                }
+                // Sidebar rows and the detail row name a pane; tabs mute all of theirs
+                let panes: Vec<u32> = if row > 0 {
+                    vec![pane_id]
+                } else {
+                    self.sessions
+                        .values()
+                        .filter(|s| s.tab_index == Some(tab))
+                        .map(|s| s.pane_id)
+                        .collect()
+                };
+                self.toggle_mute(&panes);
+                true
+            }
+            Event::Mouse(Mouse::Hover(line, col)) => self.hover_at(line, col),
+            Event::Mouse(Mouse::ScrollUp(_)) => self.scroll_bar(false),
+            Event::Mouse(Mouse::ScrollDown(_)) => self.scroll_bar(true),
+            Event::Mouse(Mouse::LeftClick(line, col)) => {
+                let row = line.max(0) as usize;
 
                 // Check prefix click region first → toggle ViewMode
                 if let Some((start, end)) = self.prefix_click_region {
-                    if col >= start && col < end {
-                        self.view_mode = match self.view_mode {
-                            ViewMode::Normal => ViewMode::Settings,
-                            ViewMode::Settings => ViewMode::Normal,
-                        };
+                    if row == 0 && col >= start && col < end {
+                        self.toggle_menu();
                         return true;
                     }
                 }
+                if let Some((start, end)) = self.overview_click_region {
+                    if row == 0 && col >= start && col < end {
+                        // Zellij's session manager lists (and switches to) the others
+                        pipe_message_to_plugin(
+                            MessageToPlugin::new("zellaude:open")
+                                .with_plugin_url("session-manager")
+                                .new_plugin_instance_should_float(true),
+                        );
+                        return false;
+                    }
+                }
+
+                if let Some((start, end)) = self.history_click_region {
+                    if row == 0 && col >= start && col < end {
+                        let _ = self.history_step(true);
+                        return false;
+                    }
+                }
+                if let Some((start, end)) = self.jump_back_click_region {
+                    if row == 0 && col >= start && col < end {
+                        self.jump_back();
+                        return false;
+                    }
+                }
+                if let Some((start, end)) = self.queue_click_region {
+                    if row == 0 && col >= start && col < end {
+                        // Oldest request first
+                        if let Some(request) = self.permission_queue.first() {
+                            focus_terminal_pane(request.pane_id, false);
+                        }
+                        return false;
+                    }
+                }
+                if let Some((start, end)) = self.launch_click_region {
+                    if row == 0 && col >= start && col < end {
+                        return self.open_launcher();
+                    }
+                }
 
                 match self.view_mode {
                     ViewMode::Normal => {
+                        if let Some((pane_id, allow)) = self
+                            .approve_click_regions
+                            .iter()
+                            .find(|r| r.row == row && col >= r.start_col && col < r.end_col)
+                            .map(|r| (r.pane_id, r.allow))
+                        {
+                            return self.answer_prompt(pane_id, allow);
+                        }
+                        if let Some(bucket) = self
+                            .summary_click_regions
+                            .iter()
+                            .find(|r| row == 0 && col >= r.start_col && col < r.end_col)
+                            .map(|r| r.bucket)
+                        {
+                            self.cycle_summary_focus(bucket);
+                            return false;
+                        }
                         for region in &self.click_regions {
-                            if col >= region.start_col && col < region.end_col {
-                                if region.is_waiting {
+                            if region.row == row && col >= region.start_col && col < region.end_col
+                            {
+                                // Double click on a tab: rename it
+                                let now_ms = unix_now_ms();
+                                let tab_click = (row == 0).then_some((region.tab_index, now_ms));
+                                let double = self.last_tab_click.is_some_and(|(tab, at)| {
+                                    tab == region.tab_index
+                                        && now_ms.saturating_sub(at) < DOUBLE_CLICK_MS
+                                });
+                                if row == 0 && double {
+                                    self.last_tab_click = None;
+                                    switch_tab_to(region.tab_index as u32 + 1);
+                                    switch_to_input_mode(&InputMode::RenameTab);
+                                    return false;
+                                }
+                                self.last_tab_click = tab_click;
+                                if region.focus_pane {
                                     focus_terminal_pane(region.pane_id, false);
                                 } else {
                                     switch_tab_to(region.tab_index as u32 + 1);
@@ -95,50 +272,74 @@ impl ZellijPlugin for State {
                         false
                     }
                     ViewMode::Settings => {
-                        for region in &self.menu_click_regions {
-                            if col >= region.start_col && col < region.end_col {
-                                match &region.action {
-                                    MenuAction::ToggleSetting(key) => {
-                                        match key {
-                                            state::SettingKey::Notifications => {
-                                                self.settings.notifications =
-                                                    self.settings.notifications.cycle();
-                                            }
-                                            state::SettingKey::Flash => {
-                                                self.settings.flash =
-                                                    self.settings.flash.cycle();
-                                            }
-                                            state::SettingKey::ElapsedTime => {
-                                                self.settings.elapsed_time =
-                                                    !self.settings.elapsed_time;
-                                            }
-                                            state::SettingKey::ModeIndicator => {
-                                                self.settings.mode_indicator =
-                                                    !self.settings.mode_indicator;
-                                            }
-                                        }
-                                        self.save_config();
-                                    }
-                                    MenuAction::CloseMenu => {
-                                        self.view_mode = ViewMode::Normal;
-                                    }
-                                }
-                                return true;
+                        let action = self
+                            .menu_click_regions
+                            .iter()
+                            .find(|r| row == 0 && col >= r.start_col && col < r.end_col)
+                            .map(|r| r.action);
+                        match action {
+                            Some(MenuAction::Activate(index)) => self.activate_menu_entry(index),
+                            Some(MenuAction::Scroll(index)) => {
+                                self.menu_scroll = index;
+                                self.menu_selected = index;
                             }
+                            Some(MenuAction::Back) => self.menu_back(),
+                            Some(MenuAction::CloseMenu) => self.view_mode = ViewMode::Normal,
+                            Some(MenuAction::Launch(_)) | None => return false,
                         }
-                        false
+                        true
+                    }
+                    ViewMode::Launcher => {
+                        let action = self
+                            .menu_click_regions
+                            .iter()
+                            .find(|r| row == 0 && col >= r.start_col && col < r.end_col)
+                            .map(|r| r.action);
+                        match action {
+                            Some(MenuAction::Launch(index)) => {
+                                let cwd = index.and_then(|i| self.recent_cwds.get(i).cloned());
+                                self.launch(cwd);
+                            }
+                            Some(MenuAction::CloseMenu) => self.view_mode = ViewMode::Normal,
+                            _ => return false,
+                        }
+                        true
                     }
                 }
             }
             Event::RunCommandResult(exit_code, stdout, _stderr, context) => {
                 match context.get("type").map(|s| s.as_str()) {
+                    Some("config_dir") if exit_code == Some(0) => {
+                        let dir = String::from_utf8_lossy(&stdout).trim().to_string();
+                        // Map the settings directory to /host so the file can
+                        // be read and written without spawning a shell
+                        change_host_folder(dir.clone().into());
+                        self.config_dir = Some(dir);
+                        false
+                    }
                     Some("load_config") if exit_code == Some(0) => {
+                        self.config_read(&String::from_utf8_lossy(&stdout))
+                    }
+                    Some("utc_offset") if exit_code == Some(0) => {
                         let raw = String::from_utf8_lossy(&stdout);
-                        if let Ok(settings) = serde_json::from_str::<Settings>(raw.trim()) {
-                            self.settings = settings;
+                        if let Some(offset) = parse_utc_offset(raw.trim()) {
+                            self.utc_offset_secs = offset;
                         }
-                        self.config_loaded = true;
-                        true
+                        self.settings.clock
+                    }
+                    Some("overview") if exit_code == Some(0) => {
+                        let Some(ref name) = self.zellij_session_name else {
+                            return false;
+                        };
+                        let others = overview::parse_others(
+                            &String::from_utf8_lossy(&stdout),
+                            name,
+                            unix_now(),
+                        );
+                        let mut msg = MessageToPlugin::new("zellaude:overview");
+                        msg.message_payload = serde_json::to_string(&others).ok();
+                        pipe_message_to_plugin(msg);
+                        self.set_other_sessions(others)
                     }
                     Some("install_hooks") => {
                         self.hooks_installed = true;
@@ -148,15 +349,45 @@ impl ZellijPlugin for State {
                 }
             }
             Event::Timer(_) => {
+                self.tick_fired();
+                let config_changed = self.poll_config();
+                self.sync_overview();
+                let dnd_changed = self.check_dnd_end();
                 let stale_changed = self.cleanup_stale_sessions();
+                let queue_changed = self.prune_permission_queue();
+                let stall_changed = self.detect_stalls();
                 let flash_changed = self.cleanup_expired_flashes();
+                let hover_changed = self.expire_hover();
                 let has_flashes = self.has_active_flashes();
-                if has_flashes {
-                    set_timeout(FLASH_TICK);
-                } else {
-                    set_timeout(TIMER_INTERVAL);
+                self.schedule_tick(self.next_tick_secs());
+                has_flashes
+                    || config_changed
+                    || dnd_changed
+                    || stale_changed
+                    || queue_changed
+                    || stall_changed
+                    || flash_changed
+                    || hover_changed
+                    || self.has_elapsed_display()
+                    || (self.settings.summary && self.settings.clock)
+                    || self.rows > 1
+            }
+            Event::HostFolderChanged(_) => {
+                self.config_on_host = true;
+                self.load_config()
+            }
+            Event::FailedToChangeHostFolder(err) => {
+                eprintln!("zellaude: can't open settings directory, using shell fallback: {err:?}");
+                self.config_on_host = false;
+                self.load_config()
+            }
+            Event::BeforeClose => {
+                if self.is_leader {
+                    if let Some(ref name) = self.zellij_session_name {
+                        overview::remove(name);
+                    }
                 }
-                has_flashes || stale_changed || flash_changed || self.has_elapsed_display()
+                false
             }
             Event::PermissionRequestResult(_) => {
                 // Now that permissions are granted, mark as non-selectable
@@ -169,6 +400,8 @@ impl ZellijPlugin for State {
                 if !self.config_loaded {
                     self.load_config();
                 }
+                // The WASM sandbox has no timezone data; ask the host for it
+                self.load_utc_offset();
                 // Auto-install hook script and register Claude Code hooks
                 if !self.hooks_installed {
                     installer::run_install();
@@ -183,15 +416,39 @@ impl ZellijPlugin for State {
         match pipe_message.name.as_str() {
             "zellaude" => {
                 // Hook event from CLI
-                let payload_str = match pipe_message.payload {
-                    Some(ref s) => s,
-                    None => return false,
-                };
-                let payload: HookPayload = match serde_json::from_str(payload_str) {
-                    Ok(p) => p,
-                    Err(_) => return false,
+                let parsed = match pipe_message.payload {
+                    Some(ref raw) => event_handler::parse_payload(raw),
+                    None => Err("empty payload".to_string()),
                 };
-                event_handler::handle_hook_event(self, payload);
+                match parsed {
+                    Ok(payload) => {
+                        // The hook reads "ok muted" as: don't notify
+                        let muted = self
+                            .sessions
+                            .get(&payload.pane_id)
+                            .is_some_and(|s| s.muted);
+                        let mut reply = if muted { "ok muted" } else { "ok" }.to_string();
+                        if let Some(note) = event_handler::compat_note(payload.v) {
+                            reply = format!("{reply}: {note}");
+                        }
+                        self.reply_to_cli(&pipe_message, &reply);
+                        self.hook_protocol = Some(payload.v);
+                        self.payload_error_active = false;
+                        let pane_id = payload.pane_id;
+                        let was_waiting = self.is_waiting(pane_id);
+                        event_handler::handle_hook_event(self, payload);
+                        if !was_waiting && self.is_waiting(pane_id) {
+                            self.follow(pane_id);
+                        }
+                    }
+                    Err(err) => {
+                        eprintln!("zellaude: rejected hook payload: {err}");
+                        self.reply_to_cli(&pipe_message, &format!("error: {err}"));
+                        self.rejected_payloads += 1;
+                        self.payload_error = Some(err);
+                        self.payload_error_active = true;
+                    }
+                }
                 true
             }
             "zellaude:focus" => {
@@ -203,6 +460,43 @@ impl ZellijPlugin for State {
                 }
                 false
             }
+            "zellaude:menu" => {
+                // Keyboard control of the settings menu (e.g. bound to keys
+                // with MessagePlugin in the Zellij config)
+                match pipe_message.payload {
+                    Some(ref command) => self.menu_command(command.trim()),
+                    None => false,
+                }
+            }
+            "zellaude:cmd" => {
+                // Runtime control from scripts and keybindings
+                let command = pipe_message.payload.as_deref().unwrap_or_default();
+                match self.control_command(command.trim()) {
+                    Ok(render) => {
+                        self.reply_to_cli(&pipe_message, "ok");
+                        render
+                    }
+                    Err(err) => {
+                        self.reply_to_cli(&pipe_message, &format!("error: {err}"));
+                        false
+                    }
+                }
+            }
+            "zellaude:query" => {
+                // Scripts asking about the sessions; one instance answers
+                if let PipeSource::Cli(ref pipe_id) = pipe_message.source {
+                    if self.is_leader {
+                        let args = &pipe_message.args;
+                        let reply = match query::parse(pipe_message.payload.as_deref(), args) {
+                            Ok(query) => query::run(self, &query),
+                            Err(err) => format!("error: {err}"),
+                        };
+                        cli_pipe_output(pipe_id, &format!("{reply}\n"));
+                        unblock_cli_pipe_input(pipe_id);
+                    }
+                }
+                false
+            }
             "zellaude:request" => {
                 // Another instance asking for state — respond with ours
                 self.broadcast_sessions();
@@ -212,7 +506,25 @@ impl ZellijPlugin for State {
                 // Another instance broadcast new settings
                 if let Some(ref payload) = pipe_message.payload {
                     if let Ok(settings) = serde_json::from_str::<Settings>(payload) {
-                        self.settings = settings;
+                        self.file_settings = settings;
+                        self.apply_config_overrides();
+                        return true;
+                    }
+                }
+                false
+            }
+            "zellaude:overview" => {
+                // The leader's pick of the other sessions' snapshots
+                match pipe_message.payload.as_deref().map(serde_json::from_str) {
+                    Some(Ok(others)) => self.set_other_sessions(others),
+                    _ => false,
+                }
+            }
+            "zellaude:mute" => {
+                // Another instance (un)muted sessions
+                if let Some(ref payload) = pipe_message.payload {
+                    if let Ok(muted) = serde_json::from_str::<BTreeMap<u32, bool>>(payload) {
+                        self.apply_mute(&muted);
                         return true;
                     }
                 }
@@ -221,10 +533,14 @@ impl ZellijPlugin for State {
             "zellaude:sync" => {
                 // Another instance sharing state — merge it
                 if let Some(ref payload) = pipe_message.payload {
-                    if let Ok(sessions) =
+                    let sync = serde_json::from_str::<SyncPayload>(payload).or_else(|_| {
+                        // Older instances send just the sessions
                         serde_json::from_str::<BTreeMap<u32, SessionInfo>>(payload)
-                    {
-                        self.merge_sessions(sessions);
+                            .map(|sessions| SyncPayload { sessions, ..Default::default() })
+                    });
+                    if let Ok(sync) = sync {
+                        self.merge_sessions(sync.sessions);
+                        self.merge_permission_queue(sync.permission_queue);
                         return true;
                     }
                 }
@@ -236,6 +552,8 @@ impl ZellijPlugin for State {
 
     fn render(&mut self, rows: usize, cols: usize) {
         render::render_status_bar(self, rows, cols);
+        // State changes may have brought the next time-based update closer
+        self.schedule_tick(self.next_tick_secs());
     }
 }
 
@@ -248,6 +566,42 @@ impl State {
         }
     }
 
+    /// Notice Claude processes that ended without a SessionEnd hook (killed,
+    /// crashed, connection dropped): a command pane that exited, or a pane
+    /// whose title stopped looking like Claude's.
+    fn detect_exits(&mut self) {
+        let Some(ref manifest) = self.pane_manifest else {
+            return;
+        };
+        for pane in manifest.panes.values().flatten().filter(|p| !p.is_plugin) {
+            let Some(session) = self.sessions.get_mut(&pane.id) else {
+                continue;
+            };
+            if tab_pane_map::is_claude_title(&pane.title) && !pane.exited {
+                self.claude_titled.insert(pane.id);
+                continue;
+            }
+            let exited = if pane.exited {
+                Some(pane.exit_status)
+            } else if self.claude_titled.contains(&pane.id) {
+                Some(None)
+            } else {
+                None
+            };
+            if let Some(code) = exited {
+                self.claude_titled.remove(&pane.id);
+                if !matches!(session.activity, state::Activity::Exited(_)) {
+                    session.activity = state::Activity::Exited(code);
+                    session.stalled = false;
+                    session.tool_input = None;
+                    session.last_event_ts = unix_now();
+                    self.flash_deadlines.remove(&pane.id);
+                    self.permission_queue.retain(|r| r.pane_id != pane.id);
+                }
+            }
+        }
+    }
+
     fn refresh_session_tab_names(&mut self) {
         for session in self.sessions.values_mut() {
             if let Some((idx, name)) = self.pane_to_tab.get(&session.pane_id) {
@@ -267,11 +621,14 @@ impl State {
         let mut changed = false;
         for session in self.sessions.values_mut() {
             match session.activity {
-                state::Activity::Done | state::Activity::AgentDone => {
-                    if now.saturating_sub(session.last_event_ts) >= DONE_TIMEOUT {
-                        session.activity = state::Activity::Idle;
-                        changed = true;
-                    }
+                state::Activity::Done | state::Activity::AgentDone
+                    if !session.unseen
+                        && self.settings.done_timeout > 0
+                        && now.saturating_sub(session.last_event_ts)
+                            >= self.settings.done_timeout =>
+                {
+                    session.activity = state::Activity::Idle;
+                    changed = true;
                 }
                 _ => {}
             }
@@ -279,6 +636,360 @@ impl State {
         changed
     }
 
+    fn toggle_menu(&mut self) {
+        match self.view_mode {
+            ViewMode::Normal => {
+                self.view_mode = ViewMode::Settings;
+                self.open_menu_page(MenuPage::Root);
+            }
+            ViewMode::Settings | ViewMode::Launcher => self.view_mode = ViewMode::Normal,
+        }
+    }
+
+    /// The `+` button: start a session in the configured directory, or
+    /// offer the recent ones.
+    fn open_launcher(&mut self) -> bool {
+        if !self.settings.launch_cwd.is_empty() {
+            self.launch(Some(self.settings.launch_cwd.clone()));
+        } else if self.recent_cwds.is_empty() {
+            self.launch(None);
+        } else {
+            self.view_mode = ViewMode::Launcher;
+        }
+        true
+    }
+
+    /// Run `launch_command` in a new tab or floating pane, in `cwd` or
+    /// wherever Zellij opens new panes.
+    fn launch(&mut self, cwd: Option<String>) {
+        self.view_mode = ViewMode::Normal;
+        let command = CommandToRun {
+            path: "sh".into(),
+            args: vec!["-c".into(), format!("exec {}", self.settings.launch_command)],
+            cwd: cwd.clone().map(Into::into),
+        };
+        match self.settings.launch_target {
+            LaunchTarget::Tab => {
+                let name = cwd.as_deref().and_then(|c| c.rsplit('/').find(|p| !p.is_empty()));
+                new_tab(name, cwd.as_deref());
+                // Run once the new tab shows up focused, so the command can't
+                // take over the pane the user is in
+                self.pending_launch = Some((command, self.tabs.len(), unix_now() + LAUNCH_TIMEOUT));
+            }
+            LaunchTarget::Floating => open_command_pane_floating(command, None, BTreeMap::new()),
+        }
+    }
+
+    /// Start a launched command in its new tab, once that tab is there and
+    /// focused (Zellij appends and focuses new tabs).
+    fn run_pending_launch(&mut self) {
+        let Some((_, tabs_before, deadline)) = &self.pending_launch else {
+            return;
+        };
+        if unix_now() > *deadline {
+            self.pending_launch = None;
+            return;
+        }
+        let last = self.tabs.len().checked_sub(1);
+        if self.tabs.len() <= *tabs_before || self.active_tab_index != last {
+            return;
+        }
+        if let Some((command, ..)) = self.pending_launch.take() {
+            // Takes the place of the new tab's shell, which is back when it exits
+            open_command_pane_in_place(command, BTreeMap::new());
+        }
+    }
+
+    fn open_menu_page(&mut self, page: MenuPage) {
+        self.menu_page = page;
+        self.menu_selected = 0;
+        self.menu_scroll = 0;
+    }
+
+    fn activate_menu_entry(&mut self, index: usize) {
+        let Some(&entry) = self.menu_page.entries().get(index) else {
+            return;
+        };
+        self.menu_selected = index;
+        match entry {
+            MenuEntry::Setting(key) => {
+                let (now, offset) = (unix_now(), self.utc_offset_secs);
+                self.change_settings(|s| s.toggle(key, now, offset));
+            }
+            MenuEntry::Page(page) => self.open_menu_page(page),
+            MenuEntry::Info(state::InfoKey::Rejected) => {
+                self.rejected_payloads = 0;
+                self.payload_error = None;
+            }
+            MenuEntry::Info(_) => {}
+        }
+    }
+
+    /// Go up one menu level, closing the menu from the root page.
+    fn menu_back(&mut self) {
+        let Some(parent) = self.menu_page.parent() else {
+            self.view_mode = ViewMode::Normal;
+            return;
+        };
+        let from = self.menu_page;
+        self.open_menu_page(parent);
+        self.menu_selected = parent
+            .entries()
+            .iter()
+            .position(|e| matches!(e, MenuEntry::Page(p) if *p == from))
+            .unwrap_or(0);
+    }
+
+    /// Handle a `zellaude:menu` command: open, close, toggle, next, prev,
+    /// select or back.
+    fn menu_command(&mut self, command: &str) -> bool {
+        let len = self.menu_page.entries().len();
+        match command {
+            "open" => {
+                self.view_mode = ViewMode::Settings;
+                self.open_menu_page(MenuPage::Root);
+            }
+            "close" => self.view_mode = ViewMode::Normal,
+            "toggle" => self.toggle_menu(),
+            _ if self.view_mode != ViewMode::Settings => return false,
+            "next" => self.menu_selected = (self.menu_selected + 1) % len,
+            "prev" => self.menu_selected = (self.menu_selected + len - 1) % len,
+            "select" => self.activate_menu_entry(self.menu_selected),
+            "back" => self.menu_back(),
+            _ => return false,
+        }
+        true
+    }
+
+    /// Run a `zellaude:cmd` command. Every instance runs it (they all receive
+    /// the pipe); settings changes are made, saved and broadcast by the leader
+    /// alone, and reach the others through the broadcast.
+    fn control_command(&mut self, command: &str) -> Result<bool, String> {
+        let (verb, rest) = command.split_once(char::is_whitespace).unwrap_or((command, ""));
+        let rest = rest.trim();
+        match verb {
+            "set" => {
+                let (key, value) = rest
+                    .split_once('=')
+                    .or_else(|| rest.split_once(char::is_whitespace))
+                    .ok_or("usage: set <setting>=<value>")?;
+                let mut settings = self.file_settings.clone();
+                settings.set(key.trim(), value)?;
+                if self.is_leader {
+                    self.change_settings(|s| *s = settings);
+                }
+            }
+            "toggle" => {
+                let key =
+                    SettingKey::from_name(rest).ok_or_else(|| format!("unknown setting: {rest}"))?;
+                // Not idempotent: a second instance would flip it back
+                if self.is_leader {
+                    let (now, offset) = (unix_now(), self.utc_offset_secs);
+                    self.change_settings(|s| s.toggle(key, now, offset));
+                }
+            }
+            "clear-flashes" => self.flash_deadlines.clear(),
+            "forget-session" => {
+                let pane_id = self.command_pane(rest)?;
+                self.sessions.remove(&pane_id);
+                self.flash_deadlines.remove(&pane_id);
+                self.permission_queue.retain(|r| r.pane_id != pane_id);
+            }
+            "mark-idle" => {
+                let pane_id = self.command_pane(rest)?;
+                let session = self
+                    .sessions
+                    .get_mut(&pane_id)
+                    .ok_or_else(|| format!("no session in pane {pane_id}"))?;
+                session.activity = state::Activity::Idle;
+                session.stalled = false;
+                session.unseen = false;
+                session.tool_input = None;
+                session.last_event_ts = unix_now();
+                self.flash_deadlines.remove(&pane_id);
+                self.permission_queue.retain(|r| r.pane_id != pane_id);
+            }
+            "dnd" => {
+                let now = unix_now();
+                let until = match rest {
+                    "off" => 0,
+                    "tomorrow" => state::dnd_tomorrow(now, self.utc_offset_secs),
+                    duration => {
+                        let secs = state::parse_duration_secs(duration)?;
+                        if secs == 0 { 0 } else { now + secs }
+                    }
+                };
+                if self.is_leader {
+                    self.change_settings(|s| s.dnd_until = until);
+                }
+            }
+            "mute" | "unmute" => {
+                let pane_id = self.command_pane(rest)?;
+                if !self.sessions.contains_key(&pane_id) {
+                    return Err(format!("no session in pane {pane_id}"));
+                }
+                self.set_muted(&BTreeMap::from([(pane_id, verb == "mute")]));
+            }
+            "toggle-mute" => {
+                let pane_id = self.command_pane(rest)?;
+                self.toggle_mute(&[pane_id]);
+            }
+            "launch" => {
+                // Every instance gets the command; one is enough to launch
+                if self.is_leader {
+                    let cwd = match rest {
+                        "" if self.settings.launch_cwd.is_empty() => None,
+                        "" => Some(self.settings.launch_cwd.clone()),
+                        dir => Some(dir.to_string()),
+                    };
+                    self.launch(cwd);
+                }
+            }
+            "back" | "forward" => {
+                // Every instance follows the focus change into its own
+                // history; one is enough to make it
+                let step = self.history_step_target(verb == "back")?;
+                if self.is_leader {
+                    self.focus_history_entry(step);
+                }
+            }
+            "jump-back" => {
+                if self.jump_back.is_none() {
+                    return Err("nothing to jump back to".into());
+                }
+                // Every instance gets the command; one is enough to switch
+                if self.is_leader {
+                    self.jump_back();
+                }
+            }
+            "reload-config" => {
+                // Forget what was read, so the next read applies even if unchanged
+                self.config_raw = None;
+                self.load_config();
+            }
+            // Shorthand: `<setting> <value>`, e.g. `flash off`
+            key if SettingKey::from_name(key).is_some() && !rest.is_empty() => {
+                let mut settings = self.file_settings.clone();
+                settings.set(key, rest)?;
+                if self.is_leader {
+                    self.change_settings(|s| *s = settings);
+                }
+            }
+            "" => return Err("empty command".into()),
+            _ => return Err(format!("unknown command: {verb}")),
+        }
+        Ok(true)
+    }
+
+    /// Pane a session command refers to: the given id, or the focused pane.
+    fn command_pane(&self, arg: &str) -> Result<u32, String> {
+        if arg.is_empty() {
+            return self.focused_pane_id().ok_or_else(|| "no focused pane".to_string());
+        }
+        arg.parse().map_err(|_| format!("invalid pane id: {arg}"))
+    }
+
+    /// Problem worth showing on the bar until it's resolved.
+    fn bar_warning(&self) -> Option<String> {
+        if let Some(ref err) = self.config_error {
+            return Some(format!("zellaude.json: {err}"));
+        }
+        self.payload_error
+            .as_ref()
+            .filter(|_| self.payload_error_active)
+            .map(|err| format!("hook: {err}"))
+    }
+
+    /// Answer a `zellij pipe` invocation. Every instance receives CLI pipes,
+    /// so only the leader writes back.
+    fn reply_to_cli(&self, pipe_message: &PipeMessage, text: &str) {
+        if let PipeSource::Cli(ref pipe_id) = pipe_message.source {
+            if self.is_leader {
+                cli_pipe_output(pipe_id, &format!("{text}\n"));
+            }
+        }
+    }
+
+    /// Terminal pane that has focus in the active tab, if any.
+    fn focused_pane_id(&self) -> Option<u32> {
+        let tab = self.tabs.iter().find(|t| t.active)?;
+        let panes = self.pane_manifest.as_ref()?.panes.get(&tab.position)?;
+        panes
+            .iter()
+            .filter(|p| !p.is_plugin && p.is_focused)
+            .find(|p| p.is_floating == tab.are_floating_panes_visible)
+            .or_else(|| panes.iter().find(|p| !p.is_plugin && p.is_focused))
+            .map(|p| p.id)
+    }
+
+    /// When do-not-disturb ends, sum up what it held back (leader only, so
+    /// the summary arrives once). Returns true while the prefix shows DND.
+    fn check_dnd_end(&mut self) -> bool {
+        let active = self.settings.dnd_active(unix_now(), self.utc_offset_secs);
+        if self.dnd_was_active && !active {
+            if self.is_leader && (self.dnd_suppressed > 0 || self.dnd_stalls > 0) {
+                let mut parts = Vec::new();
+                let counts = [
+                    (self.dnd_suppressed, "permission request"),
+                    (self.dnd_stalls, "stall"),
+                ];
+                for (n, what) in counts {
+                    if n > 0 {
+                        let plural = if n == 1 { "" } else { "s" };
+                        parts.push(format!("{n} {what}{plural}"));
+                    }
+                }
+                notifier::notify(
+                    "Do not disturb ended",
+                    &format!("{} came up meanwhile", parts.join(" and ")),
+                );
+            }
+            self.dnd_suppressed = 0;
+            self.dnd_stalls = 0;
+        }
+        let changed = active || active != self.dnd_was_active;
+        self.dnd_was_active = active;
+        changed
+    }
+
+    /// Mark sessions that have gone quiet for longer than their activity's
+    /// stall threshold. The next hook event clears the mark.
+    fn detect_stalls(&mut self) -> bool {
+        let now = unix_now();
+        let dnd = self.settings.dnd_active(now, self.utc_offset_secs);
+        let mut changed = false;
+        for session in self.sessions.values_mut() {
+            let stalled = session
+                .stall_threshold(&self.settings)
+                .is_some_and(|secs| now.saturating_sub(session.last_event_ts) >= secs);
+            if stalled == session.stalled {
+                continue;
+            }
+            session.stalled = stalled;
+            changed = true;
+            if !stalled || !self.settings.stall_notify || session.muted {
+                continue;
+            }
+            if dnd {
+                self.dnd_stalls += 1;
+            } else if self.is_leader {
+                let what = match &session.activity {
+                    state::Activity::Tool(name) => name.clone(),
+                    _ => "Thinking".to_string(),
+                };
+                let tab = session.tab_name.as_deref().unwrap_or("?");
+                notifier::notify(
+                    "⧖ Claude Code",
+                    &format!(
+                        "Stalled in {tab}: {what} for {}",
+                        render::format_elapsed(now.saturating_sub(session.last_event_ts))
+                    ),
+                );
+            }
+        }
+        changed
+    }
+
     fn clear_flashes_on_tab(&mut self, tab_idx: usize) {
         let pane_ids: Vec<u32> = self
             .sessions
@@ -291,6 +1002,116 @@ impl State {
         }
     }
 
+    /// Track focus changes for back / forward. Returning to the previous
+    /// or next entry moves through the history instead of adding to it,
+    /// which is also how a step made by another instance shows up here.
+    fn record_focus(&mut self) {
+        let (Some(tab), Some(pane)) = (self.active_tab_index, self.focused_pane_id()) else {
+            return;
+        };
+        let pos = self.history_pos;
+        let pane_at = |i: usize| self.focus_history.get(i).map(|&(_, pane)| pane);
+        if pane_at(pos) == Some(pane) {
+            return;
+        }
+        if pos > 0 && pane_at(pos - 1) == Some(pane) {
+            self.history_pos -= 1;
+        } else if pane_at(pos + 1) == Some(pane) {
+            self.history_pos += 1;
+        } else {
+            self.focus_history.truncate(pos + 1);
+            self.focus_history.push((tab, pane));
+            if self.focus_history.len() > FOCUS_HISTORY_LEN {
+                self.focus_history.remove(0);
+            }
+            self.history_pos = self.focus_history.len() - 1;
+        }
+    }
+
+    /// The history entry one step back or forward.
+    fn history_step_target(&self, back: bool) -> Result<(usize, u32), String> {
+        let index = if back {
+            self.history_pos.checked_sub(1)
+        } else {
+            Some(self.history_pos + 1)
+        };
+        index
+            .and_then(|i| self.focus_history.get(i).copied())
+            .ok_or_else(|| format!("nothing to go {}", if back { "back" } else { "forward" }))
+    }
+
+    fn history_step(&self, back: bool) -> Result<(), String> {
+        let step = self.history_step_target(back)?;
+        self.focus_history_entry(step);
+        Ok(())
+    }
+
+    /// Focus a history entry's pane, or just its tab if the pane is gone.
+    fn focus_history_entry(&self, (tab, pane): (usize, u32)) {
+        if self.pane_to_tab.contains_key(&pane) {
+            focus_terminal_pane(pane, false);
+        } else {
+            switch_tab_to(tab as u32 + 1);
+        }
+    }
+
+    fn is_waiting(&self, pane_id: u32) -> bool {
+        self.sessions
+            .get(&pane_id)
+            .is_some_and(|s| matches!(s.activity, state::Activity::Waiting))
+    }
+
+    /// Follow mode: bring a new permission request into view, unless the
+    /// user is busy, i.e. typing or watching a working session. Every
+    /// instance remembers the tab to jump back to; the leader switches.
+    fn follow(&mut self, pane_id: u32) {
+        let Some(session) = self.sessions.get(&pane_id) else {
+            return;
+        };
+        let Some(active) = self.active_tab_index else {
+            return;
+        };
+        if !self.settings.follow
+            || session.muted
+            || session.tab_index == Some(active)
+            || self.settings.dnd_active(unix_now(), self.utc_offset_secs)
+            || unix_now_ms().saturating_sub(self.last_input_ms) < FOLLOW_IDLE_MS
+        {
+            return;
+        }
+        let busy = self.sessions.values().any(|s| {
+            s.tab_index == Some(active)
+                && matches!(
+                    s.activity,
+                    state::Activity::Thinking
+                        | state::Activity::Tool(_)
+                        | state::Activity::Waiting
+                )
+        });
+        if busy {
+            return;
+        }
+        self.jump_back = Some(active);
+        if self.is_leader {
+            focus_terminal_pane(pane_id, false);
+        }
+    }
+
+    /// Return to the tab follow mode moved away from.
+    fn jump_back(&mut self) {
+        if let Some(tab) = self.jump_back.take() {
+            switch_tab_to(tab as u32 + 1);
+        }
+    }
+
+    fn mark_tab_seen(&mut self, tab_idx: usize) {
+        for session in self.sessions.values_mut() {
+            if session.tab_index == Some(tab_idx) {
+                session.unseen = false;
+            }
+        }
+    }
+
     fn has_active_flashes(&self) -> bool {
         let now = unix_now_ms();
         self.flash_deadlines.values().any(|&deadline| now < deadline)
@@ -303,61 +1124,420 @@ impl State {
         self.flash_deadlines.len() != before
     }
 
+    /// Mouse moved over the bar: show the tooltip for a tab with sessions,
+    /// or drop it when the mouse is elsewhere.
+    fn hover_at(&mut self, line: isize, col: usize) -> bool {
+        let before = self.hover.map(|(tab, _)| tab);
+        // A tooltip over the tabs would hide the ✓ / ✗ being aimed at; one
+        // that shows them stays put
+        if self
+            .approve_click_regions
+            .iter()
+            .any(|r| r.row == 0 && line == 0 && col >= r.start_col && col < r.end_col)
+        {
+            return false;
+        }
+        let tab = self
+            .click_regions
+            .iter()
+            .find(|r| r.row == 0 && line == 0 && col >= r.start_col && col < r.end_col)
+            .map(|r| r.tab_index)
+            .filter(|&tab| {
+                self.view_mode == ViewMode::Normal
+                    && self.sessions.values().any(|s| s.tab_index == Some(tab))
+            });
+        self.hover = tab.map(|tab| (tab, unix_now_ms()));
+        if tab.is_some() {
+            self.schedule_tick(HOVER_TIMEOUT_MS as f64 / 1000.0);
+        }
+        tab != before
+    }
+
+    /// Answer the permission prompt in `pane_id` with ✓ (yes) or ✗ (no).
+    /// Keys only go to that pane, once per request, and not after the pane
+    /// got input: the prompt may have been answered since the bar was drawn.
+    fn answer_prompt(&mut self, pane_id: u32, allow: bool) -> bool {
+        let waiting = self
+            .sessions
+            .get(&pane_id)
+            .is_some_and(|s| matches!(s.activity, state::Activity::Waiting));
+        if self.settings.quick_approve && waiting && self.answered_prompts.insert(pane_id) {
+            let keys = if allow { APPROVE_KEYS } else { DENY_KEYS };
+            write_chars_to_pane_id(keys, PaneId::Terminal(pane_id));
+            return true;
+        }
+        false
+    }
+
+    fn expire_hover(&mut self) -> bool {
+        match self.hover {
+            Some((_, at)) if unix_now_ms() >= at + HOVER_TIMEOUT_MS => {
+                self.hover = None;
+                true
+            }
+            _ => false,
+        }
+    }
+
     fn has_elapsed_display(&self) -> bool {
         if !self.settings.elapsed_time {
             return false;
         }
         let now = unix_now();
         self.sessions.values().any(|s| {
-            !matches!(s.activity, state::Activity::Idle)
-                && now.saturating_sub(s.last_event_ts) >= DONE_TIMEOUT
+            !matches!(s.activity, state::Activity::Idle | state::Activity::Exited(_))
+                && now.saturating_sub(s.last_event_ts) >= self.settings.elapsed_threshold
         })
     }
 
+    /// Focus the next session in `bucket`, cycling in tab order on repeated clicks.
+    fn cycle_summary_focus(&mut self, bucket: SummaryBucket) {
+        let mut panes: Vec<(usize, u32)> = self
+            .sessions
+            .values()
+            .filter(|s| SummaryBucket::of(&s.activity) == Some(bucket))
+            .map(|s| (s.tab_index.unwrap_or(usize::MAX), s.pane_id))
+            .collect();
+        if panes.is_empty() {
+            return;
+        }
+        panes.sort();
+        let next = self
+            .summary_cursor
+            .and_then(|cursor| panes.iter().position(|&(_, p)| p == cursor))
+            .map_or(0, |i| (i + 1) % panes.len());
+        let pane_id = panes[next].1;
+        self.summary_cursor = Some(pane_id);
+        focus_terminal_pane(pane_id, false);
+    }
+
+    /// Make sure a timer fires within `secs`. Timers can't be cancelled, so
+    /// one is only added when none of the pending ones fires soon enough.
+    fn schedule_tick(&mut self, secs: f64) {
+        let due = unix_now_ms() + (secs * 1000.0) as u64;
+        // Allow a little slack so near-identical deadlines share a timer
+        if self.pending_ticks.iter().any(|&t| t <= due + 100) {
+            return;
+        }
+        set_timeout(secs);
+        self.pending_ticks.push(due);
+    }
+
+    fn tick_fired(&mut self) {
+        if let Some(i) = self
+            .pending_ticks
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, &t)| t)
+            .map(|(i, _)| i)
+        {
+            self.pending_ticks.swap_remove(i);
+        }
+    }
+
+    /// Seconds until something on the bar changes without an event: a flash
+    /// frame, an elapsed counter rolling over, Done fading, or the clock.
+    fn next_tick_secs(&self) -> f64 {
+        if self.has_active_flashes() {
+            return FLASH_TICK;
+        }
+        let now = unix_now();
+        // Counters show seconds below a minute, then minutes, then hours
+        let until_counter_changes = |age: u64| match age {
+            0..60 => 1,
+            60..3600 => 60 - age % 60,
+            _ => 3600 - age % 3600,
+        };
+        let mut next = MAX_TIMER_INTERVAL as u64;
+        if self.config_loaded {
+            next = next.min(CONFIG_POLL_INTERVAL);
+        }
+        if self.config_on_host {
+            next = next.min(OVERVIEW_INTERVAL);
+        }
+        if self.settings.dnd_until > now {
+            next = next.min(self.settings.dnd_until - now);
+        }
+        if self.settings.summary && self.settings.clock {
+            let local = (now as i64 + self.utc_offset_secs).rem_euclid(60) as u64;
+            next = next.min(60 - local);
+        }
+        for s in self.sessions.values() {
+            let age = now.saturating_sub(s.last_event_ts);
+            if matches!(s.activity, state::Activity::Done | state::Activity::AgentDone)
+                && !s.unseen
+                && self.settings.done_timeout > age
+            {
+                next = next.min(self.settings.done_timeout - age);
+            }
+            // Detail rows and the sidebar always show elapsed time
+            if self.rows > 1 {
+                next = next.min(until_counter_changes(age));
+            } else if self.settings.elapsed_time
+                && !matches!(s.activity, state::Activity::Idle | state::Activity::Exited(_))
+            {
+                next = next.min(match self.settings.elapsed_threshold.checked_sub(age) {
+                    Some(wait) if wait > 0 => wait,
+                    _ => until_counter_changes(age),
+                });
+            }
+        }
+        let secs = (next as f64).max(TIMER_INTERVAL);
+        match self.hover {
+            Some((_, at)) => {
+                let left = (at + HOVER_TIMEOUT_MS).saturating_sub(unix_now_ms());
+                secs.min(left as f64 / 1000.0).max(FLASH_TICK)
+            }
+            None => secs,
+        }
+    }
+
+    /// Rebuild the settings in effect from the file's, with the layout's
+    /// overrides on top.
+    fn apply_config_overrides(&mut self) {
+        self.settings = self.file_settings.clone();
+        for (key, value) in &self.config_overrides {
+            if let Err(err) = self.settings.set(key, value) {
+                eprintln!("zellaude: layout config: {err}");
+            }
+        }
+    }
+
     fn request_sync(&self) {
         pipe_message_to_plugin(MessageToPlugin::new("zellaude:request"));
     }
 
     fn broadcast_sessions(&self) {
+        let sync = SyncPayload {
+            sessions: self.sessions.clone(),
+            permission_queue: self.permission_queue.clone(),
+        };
         let mut msg = MessageToPlugin::new("zellaude:sync");
-        msg.message_payload =
-            Some(serde_json::to_string(&self.sessions).unwrap_or_default());
+        msg.message_payload = Some(serde_json::to_string(&sync).unwrap_or_default());
         pipe_message_to_plugin(msg);
     }
 
+    /// Mouse wheel over the bar: change tab, or scroll the tab list.
+    fn scroll_bar(&mut self, forward: bool) -> bool {
+        match self.settings.scroll_action {
+            ScrollAction::SwitchTab => {
+                if forward {
+                    go_to_next_tab();
+                } else {
+                    go_to_previous_tab();
+                }
+                false
+            }
+            ScrollAction::ScrollTabs => {
+                let before = self.tab_scroll;
+                self.tab_scroll = if forward {
+                    (self.tab_scroll + 1).min(self.tab_scroll_max)
+                } else {
+                    self.tab_scroll.saturating_sub(1)
+                };
+                self.tab_scroll != before
+            }
+        }
+    }
+
+    /// Focus the session that most needs attention: the longest-waiting
+    /// permission request, else a stalled or crashed session.
+    fn focus_urgent(&self) {
+        let urgency = |s: &SessionInfo| match s.activity {
+            state::Activity::Waiting => 2,
+            _ if s.stalled || s.activity.is_crash() => 1,
+            _ => 0,
+        };
+        if let Some(session) = self
+            .sessions
+            .values()
+            .filter(|s| urgency(s) > 0)
+            .max_by_key(|s| (urgency(s), std::cmp::Reverse(s.last_event_ts)))
+        {
+            focus_terminal_pane(session.pane_id, false);
+        }
+    }
+
+    /// Mute the given sessions, or unmute them if they're all muted already.
+    fn toggle_mute(&mut self, panes: &[u32]) {
+        let mute = panes
+            .iter()
+            .any(|id| self.sessions.get(id).is_some_and(|s| !s.muted));
+        let muted: BTreeMap<u32, bool> = panes.iter().map(|&id| (id, mute)).collect();
+        self.set_muted(&muted);
+    }
+
+    /// Apply mute flags here and in the other instances.
+    fn set_muted(&mut self, muted: &BTreeMap<u32, bool>) {
+        self.apply_mute(muted);
+        let mut msg = MessageToPlugin::new("zellaude:mute");
+        msg.message_payload = Some(serde_json::to_string(muted).unwrap_or_default());
+        pipe_message_to_plugin(msg);
+    }
+
+    fn apply_mute(&mut self, muted: &BTreeMap<u32, bool>) {
+        for (pane_id, &mute) in muted {
+            if let Some(session) = self.sessions.get_mut(pane_id) {
+                session.muted = mute;
+                if mute {
+                    self.flash_deadlines.remove(pane_id);
+                }
+            }
+        }
+    }
+
     fn broadcast_settings(&self) {
         let mut msg = MessageToPlugin::new("zellaude:settings");
         msg.message_payload =
-            Some(serde_json::to_string(&self.settings).unwrap_or_default());
+            Some(serde_json::to_string(&self.file_settings).unwrap_or_default());
         pipe_message_to_plugin(msg);
     }
 
-    fn load_config(&self) {
+    /// Re-read the settings file every few seconds to pick up external edits.
+    fn poll_config(&mut self) -> bool {
+        let now = unix_now();
+        if self.config_loaded && now.saturating_sub(self.last_config_poll) >= CONFIG_POLL_INTERVAL
+        {
+            self.last_config_poll = now;
+            return self.load_config();
+        }
+        false
+    }
+
+    /// Every few seconds, publish this session's activity and pick up the
+    /// other sessions'. The leader does the exchange and passes the result
+    /// on to the other instances.
+    fn sync_overview(&mut self) {
+        let now = unix_now();
+        if !self.is_leader || now.saturating_sub(self.last_overview_sync) < OVERVIEW_INTERVAL {
+            return;
+        }
+        self.last_overview_sync = now;
+        if let Some(ref name) = self.zellij_session_name {
+            overview::exchange(&overview::Snapshot::new(name, now, self.sessions.values()));
+        }
+    }
+
+    /// Take the other sessions' snapshots, from the exchange or the leader.
+    fn set_other_sessions(&mut self, others: Vec<overview::Snapshot>) -> bool {
+        let others = if self.settings.other_sessions { others } else { Vec::new() };
+        let changed = overview::totals(&others) != overview::totals(&self.other_sessions);
+        self.other_sessions = others;
+        changed
+    }
+
+    /// Read the settings file. Through the host folder this happens right
+    /// away; otherwise the result arrives later as a `load_config` command.
+    fn load_config(&mut self) -> bool {
+        match &self.config_dir {
+            None => {
+                config::resolve_dir();
+                false
+            }
+            Some(_) if self.config_on_host => match config::read_host() {
+                Ok(raw) => self.config_read(&raw),
+                Err(err) => {
+                    let changed = self.config_error.as_ref() != Some(&err);
+                    self.config_error = Some(err);
+                    changed
+                }
+            },
+            Some(dir) => {
+                config::read_shell(dir);
+                false
+            }
+        }
+    }
+
+    fn config_read(&mut self, raw: &str) -> bool {
+        let raw = raw.trim();
+        let first_load = !self.config_loaded;
+        self.config_loaded = true;
+        if self.config_raw.as_deref() == Some(raw) {
+            return false;
+        }
+        match config::parse(raw) {
+            Ok((settings, version)) => {
+                let before = serde_json::to_string(&self.file_settings).ok();
+                self.file_settings = settings;
+                self.apply_config_overrides();
+                self.config_version = version;
+                self.config_error = (version > config::CONFIG_VERSION).then(|| {
+                    format!("written by a newer zellaude (v{version}), changes won't be saved")
+                });
+                // Edited on disk: let the other instances know, unless one
+                // of them already did
+                let after = serde_json::to_string(&self.file_settings).ok();
+                if !first_load && after != before {
+                    self.broadcast_settings();
+                }
+            }
+            // Keep the previous values, but say so on the bar
+            Err(err) => self.config_error = Some(err),
+        }
+        self.config_raw = Some(raw.to_string());
+        true
+    }
+
+    fn load_utc_offset(&self) {
         let mut ctx = BTreeMap::new();
-        ctx.insert("type".into(), "load_config".into());
-        run_command(
-            &[
-                "sh",
-                "-c",
-                "cat \"$HOME/.config/zellij/plugins/zellaude.json\" 2>/dev/null || echo '{}'",
-            ],
-            ctx,
-        );
-    }
-
-    fn save_config(&self) {
+        ctx.insert("type".into(), "utc_offset".into());
+        run_command(&["date", "+%z"], ctx);
+    }
+
+    /// Change the settings kept in the file, then apply and save them.
+    fn change_settings(&mut self, change: impl FnOnce(&mut Settings)) {
+        change(&mut self.file_settings);
+        self.apply_config_overrides();
+        self.save_config();
+    }
+
+    fn save_config(&mut self) {
         if !self.config_loaded {
             return;
         }
         self.broadcast_settings();
-        let json = serde_json::to_string(&self.settings).unwrap_or_default();
-        let json_esc = json.replace('\'', "'\\''");
-        let cmd = format!(
-            "mkdir -p \"$HOME/.config/zellij/plugins\" && printf '%s' '{json_esc}' > \"$HOME/.config/zellij/plugins/zellaude.json\""
-        );
-        let mut ctx = BTreeMap::new();
-        ctx.insert("type".into(), "save_config".into());
-        run_command(&["sh", "-c", &cmd], ctx);
+        if self.config_version > config::CONFIG_VERSION {
+            return;
+        }
+        let json = serde_json::to_string_pretty(&self.file_settings).unwrap_or_default();
+        // Our own write shouldn't look like an external edit to the poller
+        self.config_raw = Some(json.clone());
+        self.config_error = None;
+        match &self.config_dir {
+            Some(_) if self.config_on_host => {
+                if let Err(err) = config::write_host(&json, self.plugin_id) {
+                    self.config_error = Some(format!("can't save settings: {err}"));
+                }
+            }
+            Some(dir) => config::write_shell(dir, &json),
+            None => {}
+        }
+    }
+
+    /// Add requests another instance queued that this one missed, keeping
+    /// the queue in arrival order.
+    fn merge_permission_queue(&mut self, incoming: Vec<PermissionRequest>) {
+        for request in incoming {
+            if !self.permission_queue.iter().any(|r| r.pane_id == request.pane_id) {
+                self.permission_queue.push(request);
+            }
+        }
+        self.permission_queue.sort_by_key(|r| r.ts_ms);
+        self.prune_permission_queue();
+    }
+
+    /// Drop queued requests whose session no longer waits (answered, gone
+    /// or marked idle).
+    fn prune_permission_queue(&mut self) -> bool {
+        let before = self.permission_queue.len();
+        let sessions = &self.sessions;
+        self.permission_queue.retain(|r| {
+            sessions
+                .get(&r.pane_id)
+                .is_some_and(|s| matches!(s.activity, state::Activity::Waiting))
+        });
+        self.permission_queue.len() != before
     }
 
     fn merge_sessions(&mut self, incoming: BTreeMap<u32, SessionInfo>) {
@@ -378,3 +1558,18 @@ impl State {
         }
     }
 }
+
+/// Parse `date +%z` output (e.g. `+0530`) into seconds east of UTC.
+fn parse_utc_offset(raw: &str) -> Option<i64> {
+    let (sign, digits) = match raw.split_at_checked(1)? {
+        ("+", d) => (1, d),
+        ("-", d) => (-1, d),
+        _ => return None,
+    };
+    if digits.len() != 4 {
+        return None;
+    }
+    let hours: i64 = digits[..2].parse().ok()?;
+    let minutes: i64 = digits[2..].parse().ok()?;
+    Some(sign * (hours * 3600 + minutes * 60))
+}
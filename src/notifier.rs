@@ -0,0 +1,23 @@
+use std::collections::BTreeMap;
+use zellij_tile::prelude::run_command;
+
+/// Show a desktop notification on the host. Title and message are passed as
+/// arguments, so they need no quoting.
+pub fn notify(title: &str, message: &str) {
+    let mut ctx = BTreeMap::new();
+    ctx.insert("type".into(), "notify".into());
+    run_command(
+        &[
+            "sh",
+            "-c",
+            r#"case "$(uname)" in
+  Darwin) osascript -e 'on run argv' -e 'display notification (item 2 of argv) with title (item 1 of argv)' -e 'end run' "$1" "$2" ;;
+  *) command -v notify-send >/dev/null 2>&1 && notify-send "$1" "$2" ;;
+esac"#,
+            "sh",
+            title,
+            message,
+        ],
+        ctx,
+    );
+}
@@ -0,0 +1,103 @@
+use crate::state::{SessionInfo, SummaryBucket};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use zellij_tile::prelude::run_command;
+
+/// Shared by every Zellij session. A cache, not settings: it's rewritten
+/// every few seconds, so it stays out of the (often dotfile-managed) config
+/// directory.
+const DIR: &str = "${XDG_CACHE_HOME:-$HOME/.cache}/zellaude";
+/// Snapshots not refreshed for this long belong to sessions that are gone
+const STALE_SECS: u64 = 30;
+
+/// What one Zellij session's bar publishes for the others.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub session: String,
+    pub ts: u64,
+    pub counts: Vec<(SummaryBucket, usize)>,
+}
+
+impl Snapshot {
+    pub fn new<'a>(
+        session: &str,
+        ts: u64,
+        sessions: impl Iterator<Item = &'a SessionInfo> + Clone,
+    ) -> Self {
+        let counts = SummaryBucket::ALL
+            .into_iter()
+            .map(|bucket| {
+                let n = sessions
+                    .clone()
+                    .filter(|s| SummaryBucket::of(&s.activity) == Some(bucket))
+                    .count();
+                (bucket, n)
+            })
+            .filter(|&(_, n)| n > 0)
+            .collect();
+        Self {
+            session: session.to_string(),
+            ts,
+            counts,
+        }
+    }
+}
+
+fn file_name(session: &str) -> String {
+    format!("{}.json", session.replace('/', "_"))
+}
+
+/// Publish this session's snapshot (atomically, like the settings file) and
+/// print every snapshot in the directory, one per line. The result arrives
+/// as an `overview` command result, for [`parse_others`].
+pub fn exchange(snapshot: &Snapshot) {
+    let Ok(json) = serde_json::to_string(snapshot) else {
+        return;
+    };
+    let mut ctx = BTreeMap::new();
+    ctx.insert("type".into(), "overview".into());
+    let script = format!(
+        "d=\"{DIR}\"; mkdir -p \"$d\" && tmp=\"$d/.$1.$$.tmp\" \
+         && printf '%s' \"$2\" > \"$tmp\" && mv -f \"$tmp\" \"$d/$1\"; \
+         for f in \"$d\"/*.json; do [ -f \"$f\" ] && cat \"$f\" && echo; done"
+    );
+    run_command(
+        &["sh", "-c", &script, "sh", &file_name(&snapshot.session), &json],
+        ctx,
+    );
+}
+
+/// Withdraw this session's snapshot when it closes.
+pub fn remove(session: &str) {
+    let script = format!("rm -f \"{DIR}/$1\"");
+    run_command(&["sh", "-c", &script, "sh", &file_name(session)], BTreeMap::new());
+}
+
+/// Fresh snapshots of every other session from the output of [`exchange`],
+/// sorted by name.
+pub fn parse_others(output: &str, own: &str, now: u64) -> Vec<Snapshot> {
+    let mut snapshots: Vec<Snapshot> = output
+        .lines()
+        .filter_map(|line| serde_json::from_str::<Snapshot>(line).ok())
+        .filter(|s| s.session != own && now.saturating_sub(s.ts) < STALE_SECS)
+        .collect();
+    snapshots.sort_by(|a, b| a.session.cmp(&b.session));
+    snapshots
+}
+
+/// Totals per bucket across the given snapshots, in summary order.
+pub fn totals(snapshots: &[Snapshot]) -> Vec<(SummaryBucket, usize)> {
+    SummaryBucket::ALL
+        .into_iter()
+        .map(|bucket| {
+            let n = snapshots
+                .iter()
+                .flat_map(|s| &s.counts)
+                .filter(|(b, _)| *b == bucket)
+                .map(|(_, n)| n)
+                .sum();
+            (bucket, n)
+        })
+        .filter(|&(_, n)| n > 0)
+        .collect()
+}
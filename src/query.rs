@@ -0,0 +1,114 @@
+use crate::render::bucket_style;
+use crate::state::{unix_now, Activity, SessionInfo, State, SummaryBucket};
+use serde_json::json;
+use std::collections::BTreeMap;
+
+pub enum Format {
+    /// One JSON object per session, as an array
+    Json,
+    /// Summary counts on one line (`⚠2 ⚡3 ●1 ✓4`), for prompts and status lines
+    Line,
+}
+
+pub struct Query {
+    /// Activity keywords to keep (plus `stalled`, `unseen` and `crashed`); empty keeps all
+    states: Vec<String>,
+    format: Format,
+}
+
+/// Read a query from the pipe payload (`--state waiting,tool --format line`)
+/// and/or the pipe's `--args` (`state=waiting,format=line`).
+pub fn parse(payload: Option<&str>, args: &BTreeMap<String, String>) -> Result<Query, String> {
+    let mut options: Vec<(String, String)> =
+        args.iter().map(|(k, v)| (k.clone(), v.clone())).collect();
+    let mut words = payload.unwrap_or_default().split_whitespace();
+    while let Some(word) = words.next() {
+        let Some(key) = word.strip_prefix("--") else {
+            return Err(format!("unexpected argument: {word}"));
+        };
+        let value = words
+            .next()
+            .ok_or_else(|| format!("missing value for --{key}"))?;
+        options.push((key.to_string(), value.to_string()));
+    }
+
+    let mut query = Query {
+        states: Vec::new(),
+        format: Format::Json,
+    };
+    for (key, value) in options {
+        match key.as_str() {
+            "state" => query
+                .states
+                .extend(value.split(',').map(|s| s.trim().to_ascii_lowercase())),
+            "format" => {
+                query.format = match value.as_str() {
+                    "json" => Format::Json,
+                    "line" => Format::Line,
+                    _ => return Err(format!("unknown format: {value}")),
+                }
+            }
+            _ => return Err(format!("unknown option: {key}")),
+        }
+    }
+    Ok(query)
+}
+
+fn matches(query: &Query, session: &SessionInfo) -> bool {
+    query.states.is_empty()
+        || query.states.iter().any(|state| match state.as_str() {
+            "stalled" => session.stalled,
+            "unseen" => session.unseen,
+            "crashed" => session.activity.is_crash(),
+            keyword => session.activity.keyword() == keyword,
+        })
+}
+
+/// Answer a query about this Zellij session's sessions.
+pub fn run(state: &State, query: &Query) -> String {
+    let mut sessions: Vec<&SessionInfo> = state
+        .sessions
+        .values()
+        .filter(|s| matches(query, s))
+        .collect();
+    sessions.sort_by_key(|s| (s.tab_index, s.pane_id));
+
+    match query.format {
+        Format::Json => {
+            let now = unix_now();
+            let list: Vec<_> = sessions
+                .iter()
+                .map(|s| {
+                    json!({
+                        "pane_id": s.pane_id,
+                        "tab_index": s.tab_index,
+                        "tab_name": s.tab_name,
+                        "agent": s.agent,
+                        "activity": s.activity.keyword(),
+                        "tool": match s.activity {
+                            Activity::Tool(ref name) => Some(name),
+                            _ => None,
+                        },
+                        "tool_input": s.tool_input,
+                        "cwd": s.cwd,
+                        "elapsed_secs": now.saturating_sub(s.last_event_ts),
+                        "stalled": s.stalled,
+                        "unseen": s.unseen,
+                    })
+                })
+                .collect();
+            serde_json::to_string(&list).unwrap_or_default()
+        }
+        Format::Line => SummaryBucket::ALL
+            .into_iter()
+            .filter_map(|bucket| {
+                let n = sessions
+                    .iter()
+                    .filter(|s| SummaryBucket::of(&s.activity) == Some(bucket))
+                    .count();
+                (n > 0).then(|| format!("{}{n}", bucket_style(bucket).0))
+            })
+            .collect::<Vec<_>>()
+            .join(" "),
+    }
+}
@@ -1,6 +1,10 @@
+use crate::event_handler::PROTOCOL_VERSION;
+use crate::overview;
 use crate::state::{
-    unix_now, unix_now_ms, Activity, ClickRegion, FlashMode, MenuAction, MenuClickRegion,
-    NotifyMode, SessionInfo, SettingKey, State, ViewMode,
+    unix_now, unix_now_ms, Activity, ApproveClickRegion, ClickAction, ClickRegion, DetailRow,
+    FlashMode, InfoKey, LaunchTarget, LayoutMode, MenuAction, MenuClickRegion, MenuEntry,
+    NotifyMode, ScrollAction, SessionInfo, SettingKey, SidebarSort, State, SummaryBucket,
+    SummaryClickRegion, ViewMode, DEFAULT_AGENT,
 };
 use std::fmt::Write;
 use std::io::Write as IoWrite;
@@ -15,15 +19,16 @@ struct Style {
 
 fn activity_priority(activity: &Activity) -> u8 {
     match activity {
-        Activity::Waiting => 8,
-        Activity::Tool(_) => 7,
-        Activity::Thinking => 6,
-        Activity::Prompting => 5,
-        Activity::Notification => 4,
-        Activity::Init => 3,
+        Activity::Waiting => 9,
+        Activity::Tool(_) => 8,
+        Activity::Thinking => 7,
+        Activity::Prompting => 6,
+        Activity::Notification => 5,
+        Activity::Init => 4,
+        Activity::Exited(_) if activity.is_crash() => 3,
         Activity::Done => 2,
         Activity::AgentDone => 1,
-        Activity::Idle => 0,
+        Activity::Idle | Activity::Exited(_) => 0,
     }
 }
 
@@ -48,9 +53,48 @@ fn activity_style(activity: &Activity) -> Style {
         Activity::Done => Style { symbol: "✓", r: 80, g: 200, b: 120 },
         Activity::AgentDone => Style { symbol: "✓", r: 80, g: 180, b: 100 },
         Activity::Idle => Style { symbol: "○", r: 180, g: 175, b: 195 },
+        Activity::Exited(_) if activity.is_crash() => Style { symbol: "✗", r: 255, g: 90, b: 90 },
+        Activity::Exited(_) => Style { symbol: "■", r: 140, g: 135, b: 150 },
     }
 }
 
+/// Style of a session: its activity's, unless it has stalled or finished
+/// unseen.
+fn session_style(session: &SessionInfo) -> Style {
+    if session.stalled {
+        return Style { symbol: "⧖", r: 230, g: 90, b: 170 };
+    }
+    if session.unseen {
+        return Style { symbol: "★", r: 255, g: 210, b: 80 };
+    }
+    activity_style(&session.activity)
+}
+
+/// Marker shown before the activity symbol for agents other than Claude Code.
+fn agent_icon(agent: &str) -> Option<String> {
+    match agent {
+        DEFAULT_AGENT => None,
+        "codex" => Some("⌬".into()),
+        "gemini" => Some("✦".into()),
+        other => other.chars().next().map(|c| c.to_uppercase().collect()),
+    }
+}
+
+const AGENT_ICON_COLOR: Color = (165, 160, 180);
+/// Shown after the symbol of a muted session
+const MUTED_MARK: &str = "⊘";
+/// Button after the tabs that starts a new session
+const LAUNCH_BUTTON: &str = " + ";
+const LAUNCH_COLOR: Color = (80, 200, 120);
+/// " ✓ ✗" quick-approve buttons
+const APPROVE_WIDTH: usize = 4;
+const APPROVE_COLOR: Color = (80, 200, 120);
+const DENY_COLOR: Color = (255, 90, 90);
+/// Longest pending tool input shown next to the buttons on a tab
+const APPROVE_INPUT_WIDTH: usize = 16;
+/// Longest directory shown in the launcher; longer ones keep their end
+const LAUNCH_DIR_WIDTH: usize = 30;
+
 fn fg(r: u8, g: u8, b: u8) -> String {
     format!("\x1b[38;2;{r};{g};{b}m")
 }
@@ -65,8 +109,10 @@ fn display_width(s: &str) -> usize {
 
 const RESET: &str = "\x1b[0m";
 const BOLD: &str = "\x1b[1m";
-const ELAPSED_THRESHOLD: u64 = 30;
 const SEPARATOR: &str = "\u{e0b0}";
+const SEPARATOR_LEFT: &str = "\u{e0b2}";
+/// Columns the tab area keeps before the summary starts dropping parts
+const MIN_TAB_SPACE: usize = 12;
 
 type Color = (u8, u8, u8);
 const BAR_BG: Color = (30, 30, 46);
@@ -75,6 +121,12 @@ const PREFIX_BG_SETTINGS: Color = (100, 70, 140);
 const TAB_BG_ACTIVE: Color = (140, 100, 200);
 const TAB_BG_INACTIVE: Color = (80, 75, 110);
 const FLASH_BG_BRIGHT: Color = (80, 80, 30);
+const SUMMARY_BG: Color = (50, 45, 70);
+const WARNING_BG: Color = (150, 40, 40);
+const DND_BG: Color = (45, 50, 90);
+const DND_COLOR: Color = (150, 170, 255);
+/// Badge with the number of pending permission requests
+const QUEUE_BG: Color = (90, 35, 50);
 
 /// Write a powerline arrow: fg=from_bg, bg=to_bg, then separator char.
 fn arrow(buf: &mut String, col: &mut usize, from: Color, to: Color) {
@@ -87,7 +139,18 @@ fn arrow(buf: &mut String, col: &mut usize, from: Color, to: Color) {
     *col += 1;
 }
 
-fn format_elapsed(secs: u64) -> String {
+/// Write a left-pointing powerline arrow opening a right-aligned segment.
+fn arrow_left(buf: &mut String, col: &mut usize, from: Color, to: Color) {
+    let _ = write!(
+        buf,
+        "{}{}{SEPARATOR_LEFT}",
+        fg(to.0, to.1, to.2),
+        bg(from.0, from.1, from.2),
+    );
+    *col += 1;
+}
+
+pub fn format_elapsed(secs: u64) -> String {
     if secs < 60 {
         format!("{secs}s")
     } else if secs < 3600 {
@@ -116,12 +179,172 @@ fn mode_style(mode: InputMode) -> (Color, &'static str) {
     }
 }
 
-pub fn render_status_bar(state: &mut State, _rows: usize, cols: usize) {
+pub fn bucket_style(bucket: SummaryBucket) -> (&'static str, Color) {
+    match bucket {
+        SummaryBucket::Waiting => ("⚠", (255, 60, 60)),
+        SummaryBucket::Tool => ("⚡", (255, 170, 50)),
+        SummaryBucket::Thinking => ("●", (180, 140, 255)),
+        SummaryBucket::Done => ("✓", (80, 200, 120)),
+    }
+}
+
+fn format_clock(now_s: u64, utc_offset_secs: i64) -> String {
+    let secs_of_day = (now_s as i64 + utc_offset_secs).rem_euclid(86_400);
+    format!("{:02}:{:02}", secs_of_day / 3600, (secs_of_day % 3600) / 60)
+}
+
+/// One piece of the right-aligned summary: a count, the clock or the session name.
+struct SummaryPart {
+    text: String,
+    color: Color,
+    bucket: Option<SummaryBucket>,
+}
+
+fn summary_width<'a>(parts: impl IntoIterator<Item = &'a SummaryPart>) -> usize {
+    let (count, text) = parts
+        .into_iter()
+        .fold((0, 0), |(n, w), p| (n + 1, w + display_width(&p.text)));
+    if count == 0 {
+        return 0;
+    }
+    // arrow + leading space + parts separated by spaces + trailing space
+    1 + 1 + text + (count - 1) + 1
+}
+
+/// Build the summary parts, dropping the clock and then the session name
+/// until the segment fits in `avail` columns.
+fn plan_summary(state: &State, avail: usize) -> Vec<SummaryPart> {
+    if !state.settings.summary {
+        return Vec::new();
+    }
+    let mut counts = Vec::new();
+    for bucket in SummaryBucket::ALL {
+        let n = state
+            .sessions
+            .values()
+            .filter(|s| SummaryBucket::of(&s.activity) == Some(bucket))
+            .count();
+        if n > 0 {
+            let (symbol, color) = bucket_style(bucket);
+            counts.push(SummaryPart {
+                text: format!("{symbol}{n}"),
+                color,
+                bucket: Some(bucket),
+            });
+        }
+    }
+    let clock = state.settings.clock.then(|| SummaryPart {
+        text: format_clock(unix_now(), state.utc_offset_secs),
+        color: (165, 160, 180),
+        bucket: None,
+    });
+    let session = state
+        .zellij_session_name
+        .as_ref()
+        .filter(|_| state.settings.session_in_summary)
+        .map(|name| SummaryPart {
+            text: name.clone(),
+            color: (220, 215, 230),
+            bucket: None,
+        });
+
+    let mut extras: Vec<SummaryPart> = clock.into_iter().chain(session).collect();
+    while !extras.is_empty() && summary_width(counts.iter().chain(&extras)) > avail {
+        extras.remove(0);
+    }
+    counts.extend(extras);
+    if summary_width(&counts) > avail {
+        counts.clear();
+    }
+    counts
+}
+
+fn render_summary(state: &mut State, buf: &mut String, col: &mut usize, parts: &[SummaryPart]) {
+    arrow_left(buf, col, BAR_BG, SUMMARY_BG);
+    let _ = write!(buf, "{} ", bg(SUMMARY_BG.0, SUMMARY_BG.1, SUMMARY_BG.2));
+    *col += 1;
+    for (i, part) in parts.iter().enumerate() {
+        if i > 0 {
+            let _ = write!(buf, " ");
+            *col += 1;
+        }
+        let start = *col;
+        let (r, g, b) = part.color;
+        let _ = write!(buf, "{BOLD}{}{}", fg(r, g, b), part.text);
+        *col += display_width(&part.text);
+        if let Some(bucket) = part.bucket {
+            state.summary_click_regions.push(SummaryClickRegion {
+                start_col: start,
+                end_col: *col,
+                bucket,
+            });
+        }
+    }
+    let _ = write!(buf, " {RESET}");
+    *col += 1;
+}
+
+fn activity_label(activity: &Activity) -> &str {
+    match activity {
+        Activity::Init => "starting",
+        Activity::Thinking => "thinking",
+        Activity::Tool(name) => name,
+        Activity::Prompting => "prompting",
+        Activity::Waiting => "waiting",
+        Activity::Notification => "notified",
+        Activity::Done => "done",
+        Activity::AgentDone => "agent done",
+        Activity::Idle => "idle",
+        Activity::Exited(_) if activity.is_crash() => "crashed",
+        Activity::Exited(_) => "exited",
+    }
+}
+
+fn session_label(session: &SessionInfo) -> String {
+    let label = activity_label(&session.activity);
+    if let Activity::Exited(Some(code)) = session.activity {
+        format!("{label} ({code})")
+    } else if session.stalled {
+        format!("{label} stalled")
+    } else if session.unseen {
+        format!("{label}, unseen")
+    } else {
+        label.to_string()
+    }
+}
+
+/// Write `text` without going past `limit`, ending in `…` when clipped.
+/// Returns false if the text had to be clipped.
+fn write_clipped(buf: &mut String, col: &mut usize, limit: usize, text: &str) -> bool {
+    let avail = limit.saturating_sub(*col);
+    let width = display_width(text);
+    if width <= avail {
+        buf.push_str(text);
+        *col += width;
+        return true;
+    }
+    if avail > 0 {
+        let short: String = text.chars().take(avail - 1).collect();
+        let _ = write!(buf, "{short}…");
+        *col += avail;
+    }
+    false
+}
+
+pub fn render_status_bar(state: &mut State, rows: usize, cols: usize) {
     state.click_regions.clear();
     state.menu_click_regions.clear();
-
-    let mut buf = String::with_capacity(cols * 4);
-    // Terminal setup for a 1-row status bar:
+    state.summary_click_regions.clear();
+    state.approve_click_regions.clear();
+    state.overview_click_region = None;
+    state.launch_click_region = None;
+    state.queue_click_region = None;
+    state.jump_back_click_region = None;
+    state.history_click_region = None;
+    state.rows = rows;
+
+    let mut buf = String::with_capacity(cols * 4 * rows.max(1));
+    // Terminal setup for the status bar:
     //  \x1b[H     — cursor home (prevent scroll from cursor at end-of-line)
     //  \x1b[?7l   — disable auto-wrap (clip overflow instead of scroll)
     //  \x1b[?25l  — hide cursor
@@ -142,14 +365,23 @@ pub fn render_status_bar(state: &mut State, _rows: usize, cols: usize) {
         PREFIX_BG
     };
 
-    // Build prefix: " Zellaude (session) MODE "
-    let (mode_bg, mode_text) = mode_style(state.input_mode);
+    let layout = state.settings.effective_layout(rows, cols);
+    let compact = layout == LayoutMode::Compact;
+
+    // Build prefix: " Zellaude (session) MODE ", or " Z M " in compact layout
+    let (mode_bg, mut mode_text) = mode_style(state.input_mode);
     let show_mode = state.settings.mode_indicator;
+    let session_moved = state.settings.summary && state.settings.session_in_summary;
     let session_part = match state.zellij_session_name.as_deref() {
-        Some(name) => format!(" ({name})"),
-        None => String::new(),
+        Some(name) if !session_moved && !compact => format!(" ({name})"),
+        _ => String::new(),
+    };
+    let prefix_text = if compact {
+        mode_text = &mode_text[..1];
+        " Z ".to_string()
+    } else {
+        format!(" Zellaude{session_part} ")
     };
-    let prefix_text = format!(" Zellaude{session_part} ");
     let prefix_width = display_width(&prefix_text);
     let mode_pill_width = if show_mode { 1 + mode_text.len() + 1 } else { 0 };
     let total_prefix_width = prefix_width + mode_pill_width;
@@ -195,29 +427,210 @@ pub fn render_status_bar(state: &mut State, _rows: usize, cols: usize) {
     }
     state.prefix_click_region = Some((0, col));
 
-    let last_prefix_bg = if show_mode && total_prefix_width <= cols { mode_bg } else { prefix_bg };
+    let mut last_prefix_bg =
+        if show_mode && total_prefix_width <= cols { mode_bg } else { prefix_bg };
+
+    // Do-not-disturb pill with the time left (just the moon in quiet hours)
+    let now_s = unix_now();
+    if state.settings.dnd_active(now_s, state.utc_offset_secs) {
+        let text = match dnd_remaining(state) {
+            Some(left) if !compact => format!(" ☾ {left} "),
+            _ => " ☾ ".to_string(),
+        };
+        if col + 1 + display_width(&text) + MIN_TAB_SPACE <= cols {
+            arrow(&mut buf, &mut col, last_prefix_bg, DND_BG);
+            let _ = write!(
+                buf,
+                "{}{}{text}{RESET}",
+                bg(DND_BG.0, DND_BG.1, DND_BG.2),
+                fg(DND_COLOR.0, DND_COLOR.1, DND_COLOR.2),
+            );
+            col += display_width(&text);
+            last_prefix_bg = DND_BG;
+        }
+    }
+
+    // Buttons back in the focus history, and back to the tab follow mode
+    // came from
+    let buttons = [
+        (" ⟲ ", true, state.settings.history_button && state.history_pos > 0),
+        (" ↩ ", false, state.jump_back.is_some()),
+    ];
+    for (text, history, shown) in buttons {
+        if !shown || state.view_mode != ViewMode::Normal {
+            continue;
+        }
+        let width = display_width(text);
+        if col + 1 + width + MIN_TAB_SPACE <= cols {
+            arrow(&mut buf, &mut col, last_prefix_bg, SUMMARY_BG);
+            let (r, g, b) = SUMMARY_BG;
+            let _ = write!(buf, "{}{}{BOLD}{text}{RESET}", bg(r, g, b), fg(255, 255, 255));
+            let region = Some((col, col + width));
+            if history {
+                state.history_click_region = region;
+            } else {
+                state.jump_back_click_region = region;
+            }
+            col += width;
+            last_prefix_bg = SUMMARY_BG;
+        }
+    }
+
+    // Pending permission requests, e.g. " ⚠3 "; a click goes to the oldest
+    let pending = state.permission_queue.len();
+    if pending > 0 && state.view_mode == ViewMode::Normal {
+        let text = format!(" ⚠{pending} ");
+        let width = display_width(&text);
+        if col + 1 + width + MIN_TAB_SPACE <= cols {
+            arrow(&mut buf, &mut col, last_prefix_bg, QUEUE_BG);
+            let (r, g, b) = QUEUE_BG;
+            let _ = write!(buf, "{}{}{BOLD}{text}{RESET}", bg(r, g, b), fg(255, 90, 90));
+            state.queue_click_region = Some((col, col + width));
+            col += width;
+            last_prefix_bg = QUEUE_BG;
+        }
+    }
+
+    // Warning pill, e.g. when an edited settings file fails to parse
+    if let Some(warning) = state.bar_warning() {
+        let text = if compact { " ⚠ ".to_string() } else { format!(" ⚠ {warning} ") };
+        let limit = (col + 1 + display_width(&text)).min(col + cols / 3);
+        if limit + MIN_TAB_SPACE <= cols {
+            arrow(&mut buf, &mut col, last_prefix_bg, WARNING_BG);
+            let (r, g, b) = WARNING_BG;
+            let _ = write!(buf, "{}{}", fg(255, 255, 255), bg(r, g, b));
+            write_clipped(&mut buf, &mut col, limit, &text);
+            let _ = write!(buf, "{RESET}");
+            last_prefix_bg = WARNING_BG;
+        }
+    }
+
+    // Badge with the activity in other Zellij sessions, e.g. " other: ⚠1 ⚡2 "
+    let others = overview::totals(&state.other_sessions);
+    if !others.is_empty() && state.view_mode == ViewMode::Normal {
+        let label = if compact { "" } else { " other:" };
+        let counts: Vec<(String, Color)> = others
+            .iter()
+            .map(|&(bucket, n)| {
+                let (symbol, color) = bucket_style(bucket);
+                (format!(" {symbol}{n}"), color)
+            })
+            .collect();
+        let text_width =
+            display_width(label) + counts.iter().map(|(t, _)| display_width(t)).sum::<usize>() + 1;
+        if col + 1 + text_width + MIN_TAB_SPACE <= cols {
+            arrow(&mut buf, &mut col, last_prefix_bg, SUMMARY_BG);
+            let (r, g, b) = SUMMARY_BG;
+            let _ = write!(buf, "{}{}{label}", bg(r, g, b), fg(220, 215, 230));
+            for (text, (r, g, b)) in &counts {
+                let _ = write!(buf, "{}{text}", fg(*r, *g, *b));
+            }
+            let _ = write!(buf, " {RESET}");
+            state.overview_click_region = Some((col, col + text_width));
+            col += text_width;
+            last_prefix_bg = SUMMARY_BG;
+        }
+    }
     let prefix_used = col;
 
+    let mut summary: Vec<SummaryPart> = Vec::new();
     if col < cols {
         match state.view_mode {
             ViewMode::Normal => {
-                render_tabs(state, &mut buf, &mut col, cols, last_prefix_bg, prefix_used);
+                summary = plan_summary(state, cols.saturating_sub(prefix_used + MIN_TAB_SPACE));
+                let tabs_end = cols - summary_width(&summary);
+                // Room for the "+" launcher after the tabs
+                let tabs_limit = tabs_end.saturating_sub(display_width(LAUNCH_BUTTON));
+                if layout == LayoutMode::Sidebar {
+                    // Sessions are listed on the rows below the header
+                    summary.clear();
+                    arrow(&mut buf, &mut col, last_prefix_bg, BAR_BG);
+                } else if state.hover.is_some() && rows < 2 {
+                    // No detail row to put the tooltip in: it covers the tabs
+                    arrow(&mut buf, &mut col, last_prefix_bg, BAR_BG);
+                    let _ = write!(buf, "{bar_bg_str}");
+                    render_hover(state, &mut buf, &mut col, tabs_limit, 0);
+                    // The mouse still points at tabs, as they were drawn
+                    let tabs = state.tab_regions.clone();
+                    state.click_regions.extend(tabs);
+                } else {
+                    if compact {
+                        render_tabs_compact(state, &mut buf, &mut col, tabs_limit, last_prefix_bg);
+                    } else {
+                        render_tabs(
+                            state,
+                            &mut buf,
+                            &mut col,
+                            tabs_limit,
+                            last_prefix_bg,
+                            prefix_used,
+                        );
+                    }
+                    state.tab_regions =
+                        state.click_regions.iter().filter(|r| r.row == 0).cloned().collect();
+                }
+                if col + display_width(LAUNCH_BUTTON) <= tabs_end {
+                    let (r, g, b) = LAUNCH_COLOR;
+                    let _ = write!(buf, "{bar_bg_str}{}{BOLD}{LAUNCH_BUTTON}{RESET}", fg(r, g, b));
+                    let width = display_width(LAUNCH_BUTTON);
+                    state.launch_click_region = Some((col, col + width));
+                    col += width;
+                }
             }
             ViewMode::Settings => {
                 arrow(&mut buf, &mut col, last_prefix_bg, BAR_BG);
                 let _ = write!(buf, "{bar_bg_str}");
-                render_settings_menu(state, &mut buf, &mut col);
+                if compact || layout == LayoutMode::Sidebar {
+                    render_settings_item(state, &mut buf, &mut col, cols);
+                } else {
+                    render_settings_menu(state, &mut buf, &mut col, cols);
+                }
+            }
+            ViewMode::Launcher => {
+                arrow(&mut buf, &mut col, last_prefix_bg, BAR_BG);
+                let _ = write!(buf, "{bar_bg_str}");
+                render_launcher(state, &mut buf, &mut col, cols);
             }
         }
     }
 
     // Fill remaining width with bar background — never exceed cols
-    if col < cols {
-        let remaining = cols - col;
+    let fill_end = cols - summary_width(&summary);
+    if col < fill_end {
+        let remaining = fill_end - col;
         let _ = write!(buf, "{bar_bg_str}{:width$}", "", width = remaining);
+        col = fill_end;
+    }
+    if !summary.is_empty() {
+        render_summary(state, &mut buf, &mut col, &summary);
     }
     let _ = write!(buf, "{RESET}");
 
+    if layout == LayoutMode::Sidebar {
+        render_sidebar(state, &mut buf, rows, cols);
+        print!("{buf}");
+        let _ = std::io::stdout().flush();
+        return;
+    }
+
+    // Second row: details, when the pane was given size=2 or more
+    for row in 1..rows {
+        let _ = write!(buf, "\x1b[{};1H{bar_bg_str}", row + 1);
+        let mut col = 0;
+        if row == 1 && state.view_mode == ViewMode::Normal && state.hover.is_some() {
+            render_hover(state, &mut buf, &mut col, cols, 1);
+        } else if row == 1 && state.view_mode == ViewMode::Normal {
+            match state.settings.detail_row {
+                DetailRow::Session => render_session_detail(state, &mut buf, &mut col, cols),
+                DetailRow::Tabs => render_tab_details(state, &mut buf, &mut col, cols),
+            }
+        }
+        if col < cols {
+            let _ = write!(buf, "{bar_bg_str}{:width$}", "", width = cols - col);
+        }
+        let _ = write!(buf, "{RESET}");
+    }
+
     print!("{buf}");
     let _ = std::io::stdout().flush();
 }
@@ -233,11 +646,14 @@ fn render_tabs(
     let now_s = unix_now();
     let now_ms = unix_now_ms();
 
-    // Sort tabs by position
+    // Sort tabs by position, minus any scrolled off to the left
     let mut tabs: Vec<&TabInfo> = state.tabs.iter().collect();
     tabs.sort_by_key(|t| t.position);
+    let skipped = state.tab_scroll.min(tabs.len().saturating_sub(1));
+    tabs.drain(..skipped);
 
     let count = tabs.len();
+    state.tab_scroll_max = skipped;
     if count == 0 {
         arrow(buf, col, prefix_bg, BAR_BG);
         return;
@@ -264,7 +680,7 @@ fn render_tabs(
             }
             session.and_then(|s| {
                 let elapsed = now_s.saturating_sub(s.last_event_ts);
-                if elapsed >= ELAPSED_THRESHOLD {
+                if elapsed >= state.settings.elapsed_threshold {
                     Some(format_elapsed(elapsed))
                 } else {
                     None
@@ -280,7 +696,14 @@ fn render_tabs(
         .sum();
     let per_tab_overhead: usize = best_sessions
         .iter()
-        .map(|s: &Option<&SessionInfo>| if s.is_some() { 4 } else { 2 })
+        .map(|s: &Option<&SessionInfo>| match s {
+            Some(s) => {
+                4 + agent_icon(&s.agent).map_or(0, |icon| display_width(&icon))
+                    + usize::from(s.muted)
+                    + approve_tab_width(state, s)
+            }
+            None => 2,
+        })
         .sum();
     let overhead = prefix_width + 2 * count + per_tab_overhead + total_elapsed_width;
     let max_name_len = if overhead < cols {
@@ -295,6 +718,8 @@ fn render_tabs(
         // Stop if we'd overflow — need room for at least arrow + closing arrow
         let arrows_needed = if prev_bg == prefix_bg { 1 } else { 2 };
         if *col + arrows_needed + 3 > cols {
+            // The wheel may scroll off as many as didn't fit
+            state.tab_scroll_max = skipped + count - i;
             break;
         }
 
@@ -322,7 +747,7 @@ fn render_tabs(
                 state
                     .flash_deadlines
                     .get(&s.pane_id)
-                    .map(|&deadline| now_ms < deadline && (now_ms / 250) % 2 == 0)
+                    .map(|&deadline| now_ms < deadline && (now_ms / 250).is_multiple_of(2))
                     .unwrap_or(false)
             });
 
@@ -350,7 +775,7 @@ fn render_tabs(
 
         if is_claude {
             let s = session.unwrap();
-            let style = activity_style(&s.activity);
+            let style = session_style(s);
 
             let (sym_fg, name_fg, name_bold) = if is_flash_bright {
                 (fg(255, 255, 80), fg(255, 255, 80), true)
@@ -364,9 +789,19 @@ fn render_tabs(
             let _ = write!(buf, "{tab_bg_str} ");
             *col += 1;
 
-            // Symbol
+            // Agent icon + symbol
+            if let Some(icon) = agent_icon(&s.agent) {
+                let (r, g, b) = AGENT_ICON_COLOR;
+                let _ = write!(buf, "{}{icon}", fg(r, g, b));
+                *col += display_width(&icon);
+            }
             let _ = write!(buf, "{sym_fg}{}", style.symbol);
             *col += display_width(style.symbol);
+            if s.muted {
+                let (r, g, b) = AGENT_ICON_COLOR;
+                let _ = write!(buf, "{}{MUTED_MARK}", fg(r, g, b));
+                *col += 1;
+            }
 
             // Space + name
             if !truncated.is_empty() {
@@ -375,6 +810,23 @@ fn render_tabs(
                 *col += 1 + display_width(&truncated);
             }
 
+            // Quick approve: what's being asked, then ✓ / ✗
+            let approve_width = approve_tab_width(state, s);
+            if approve_width > 0 && *col + approve_width + 1 < cols {
+                if let Some(ref input) = s.tool_input {
+                    let short: String = if input.chars().count() > APPROVE_INPUT_WIDTH {
+                        let s: String = input.chars().take(APPROVE_INPUT_WIDTH - 1).collect();
+                        format!("{s}…")
+                    } else {
+                        input.clone()
+                    };
+                    let _ = write!(buf, " {}{short}", fg(200, 200, 100));
+                    *col += 1 + display_width(&short);
+                }
+                let regions = &mut state.approve_click_regions;
+                render_approve_buttons(buf, col, regions, (0, s.pane_id), &tab_bg_str);
+            }
+
             // Elapsed suffix
             if let Some(ref es) = elapsed_strs[i] {
                 if *col + 1 + es.len() + 1 < cols {
@@ -401,11 +853,12 @@ fn render_tabs(
                 .find(|s| matches!(s.activity, Activity::Waiting));
 
             state.click_regions.push(ClickRegion {
+                row: 0,
                 start_col: region_start,
                 end_col: *col,
                 tab_index: tab.position,
                 pane_id: waiting_session.map_or(0, |s| s.pane_id),
-                is_waiting: waiting_session.is_some(),
+                focus_pane: waiting_session.is_some(),
             });
         } else {
             // Non-Claude tab: dimmer, no symbol
@@ -438,11 +891,12 @@ fn render_tabs(
             *col += 1;
 
             state.click_regions.push(ClickRegion {
+                row: 0,
                 start_col: region_start,
                 end_col: *col,
                 tab_index: tab.position,
                 pane_id: 0,
-                is_waiting: false,
+                focus_pane: false,
             });
         }
 
@@ -455,6 +909,447 @@ fn render_tabs(
     }
 }
 
+/// Full-width details of the focused session, or the most urgent one when
+/// the focused pane isn't running Claude.
+fn render_session_detail(state: &mut State, buf: &mut String, col: &mut usize, cols: usize) {
+    let session = state
+        .focused_pane_id()
+        .and_then(|pane_id| state.sessions.get(&pane_id))
+        .or_else(|| {
+            state
+                .sessions
+                .values()
+                .max_by_key(|s| (activity_priority(&s.activity), s.last_event_ts))
+        });
+    let Some(session) = session else {
+        return;
+    };
+
+    let style = session_style(session);
+    let mut parts: Vec<(String, String)> = Vec::new();
+    parts.push((
+        format!("{} {}", style.symbol, session.tab_name.as_deref().unwrap_or("?")),
+        fg(style.r, style.g, style.b),
+    ));
+    parts.extend(detail_parts(session));
+
+    let approve = approvable(state, session);
+    let limit = if approve { cols.saturating_sub(APPROVE_WIDTH + 1) } else { cols };
+    let region_start = *col;
+    let _ = write!(buf, " ");
+    *col += 1;
+    write_parts(buf, col, limit, &parts);
+    if approve && *col + 1 + APPROVE_WIDTH <= cols {
+        let _ = write!(buf, " ");
+        *col += 1;
+        let bar_bg = bg(BAR_BG.0, BAR_BG.1, BAR_BG.2);
+        let regions = &mut state.approve_click_regions;
+        render_approve_buttons(buf, col, regions, (1, session.pane_id), &bar_bg);
+    }
+
+    state.click_regions.push(ClickRegion {
+        row: 1,
+        start_col: region_start,
+        end_col: *col,
+        tab_index: session.tab_index.unwrap_or(0),
+        pane_id: session.pane_id,
+        focus_pane: true,
+    });
+}
+
+/// A session whose permission prompt can be answered from the bar. Only
+/// Claude Code's prompt is known, so other agents don't get the buttons.
+/// Once the pane got input the prompt may be gone while the session still
+/// reads as waiting (until the tool finishes), so the buttons go too.
+fn approvable(state: &State, session: &SessionInfo) -> bool {
+    state.settings.quick_approve
+        && matches!(session.activity, Activity::Waiting)
+        && session.agent == DEFAULT_AGENT
+        && !state.answered_prompts.contains(&session.pane_id)
+}
+
+/// Write the " ✓ ✗" buttons answering the prompt of `pane_id` on `row`;
+/// `bg_str` restores the segment's background after each.
+fn render_approve_buttons(
+    buf: &mut String,
+    col: &mut usize,
+    regions: &mut Vec<ApproveClickRegion>,
+    (row, pane_id): (usize, u32),
+    bg_str: &str,
+) {
+    for (text, allow, (r, g, b)) in [("✓", true, APPROVE_COLOR), ("✗", false, DENY_COLOR)] {
+        let _ = write!(buf, " {BOLD}{}{text}{RESET}{bg_str}", fg(r, g, b));
+        *col += 1;
+        regions.push(ApproveClickRegion {
+            row,
+            start_col: *col,
+            end_col: *col + 1,
+            pane_id,
+            allow,
+        });
+        *col += 1;
+    }
+}
+
+/// Width of the quick-approve part of a tab: the pending input, then ✓ ✗.
+fn approve_tab_width(state: &State, session: &SessionInfo) -> usize {
+    if !approvable(state, session) {
+        return 0;
+    }
+    let input = session.tool_input.as_ref();
+    APPROVE_WIDTH + input.map_or(0, |i| 1 + i.chars().count().min(APPROVE_INPUT_WIDTH))
+}
+
+/// Details of a session after its symbol: cwd, what it's doing, time in
+/// that state and the last notification message.
+fn detail_parts(session: &SessionInfo) -> Vec<(String, String)> {
+    let elapsed = format_elapsed(unix_now().saturating_sub(session.last_event_ts));
+    let mut parts: Vec<(String, String)> = Vec::new();
+    if let Some(ref cwd) = session.cwd {
+        parts.push((cwd.clone(), fg(120, 220, 220)));
+    }
+    let doing = match session.tool_input {
+        Some(ref input) => format!("{}: {input}", session_label(session)),
+        None => session_label(session),
+    };
+    parts.push((doing, fg(255, 255, 255)));
+    parts.push((elapsed, fg(165, 160, 180)));
+    if let Some(ref message) = session.last_message {
+        parts.push((format!("“{message}”"), fg(200, 200, 100)));
+    }
+    parts
+}
+
+/// Write `parts` separated by dots, clipped at `limit`. Returns false when
+/// something was cut off.
+fn write_parts(
+    buf: &mut String,
+    col: &mut usize,
+    limit: usize,
+    parts: &[(String, String)],
+) -> bool {
+    let separator_color = fg(100, 100, 100);
+    for (i, (text, color)) in parts.iter().enumerate() {
+        if i > 0 {
+            // Color straight into buf: escapes would count as columns
+            buf.push_str(&separator_color);
+            if !write_clipped(buf, col, limit, " · ") {
+                return false;
+            }
+        }
+        buf.push_str(color);
+        if !write_clipped(buf, col, limit, text) {
+            return false;
+        }
+    }
+    true
+}
+
+/// Tooltip for the hovered tab: its full name, then each of its sessions,
+/// most urgent first. On the detail row, clicking it focuses that session;
+/// over the tabs, clicks go to the tabs underneath.
+fn render_hover(state: &mut State, buf: &mut String, col: &mut usize, limit: usize, row: usize) {
+    let Some((tab_index, _)) = state.hover else {
+        return;
+    };
+    let mut sessions: Vec<&SessionInfo> = state
+        .sessions
+        .values()
+        .filter(|s| s.tab_index == Some(tab_index))
+        .collect();
+    sessions.sort_by_key(|s| std::cmp::Reverse((activity_priority(&s.activity), s.last_event_ts)));
+    let Some(first) = sessions.first().map(|s| s.pane_id) else {
+        return;
+    };
+    let tab_name = state
+        .tabs
+        .iter()
+        .find(|t| t.position == tab_index)
+        .map_or("?", |t| t.name.as_str());
+
+    let region_start = *col;
+    let _ = write!(buf, " {}{BOLD}", fg(255, 255, 255));
+    *col += 1;
+    let mut fits = write_clipped(buf, col, limit, tab_name);
+    let bar_bg = bg(BAR_BG.0, BAR_BG.1, BAR_BG.2);
+    let _ = write!(buf, "{RESET}{bar_bg}");
+    let separator_color = fg(100, 100, 100);
+    for session in &sessions {
+        if !fits {
+            break;
+        }
+        let style = session_style(session);
+        let mut symbol = style.symbol.to_string();
+        if session.muted {
+            symbol.push_str(MUTED_MARK);
+        }
+        let mut parts = vec![(symbol, fg(style.r, style.g, style.b))];
+        buf.push_str(&separator_color);
+        fits = write_clipped(buf, col, limit, "  │ ");
+        if fits && approvable(state, session) && *col + APPROVE_WIDTH + 4 <= limit {
+            // Right after the symbol, so long details can't push them off
+            let (symbol, color) = parts.remove(0);
+            let _ = write!(buf, "{color}{symbol}");
+            *col += display_width(&symbol);
+            let regions = &mut state.approve_click_regions;
+            render_approve_buttons(buf, col, regions, (row, session.pane_id), &bar_bg);
+            buf.push_str(&separator_color);
+            fits = write_clipped(buf, col, limit, " · ");
+        }
+        parts.extend(detail_parts(session));
+        fits = fits && write_parts(buf, col, limit, &parts);
+    }
+
+    if row == 0 {
+        return;
+    }
+    state.click_regions.push(ClickRegion {
+        row,
+        start_col: region_start,
+        end_col: *col,
+        tab_index,
+        pane_id: first,
+        focus_pane: true,
+    });
+}
+
+/// Per-tab details under each tab segment of the first row.
+fn render_tab_details(state: &mut State, buf: &mut String, col: &mut usize, cols: usize) {
+    let now_s = unix_now();
+    let segments: Vec<(usize, usize, usize)> = state
+        .click_regions
+        .iter()
+        .filter(|r| r.row == 0)
+        .map(|r| (r.start_col, r.end_col.min(cols), r.tab_index))
+        .collect();
+
+    for (start, end, tab_index) in segments {
+        let Some(session) = state
+            .sessions
+            .values()
+            .filter(|s| s.tab_index == Some(tab_index))
+            .max_by_key(|s| activity_priority(&s.activity))
+        else {
+            continue;
+        };
+        if *col < start {
+            let _ = write!(buf, "{:width$}", "", width = start - *col);
+            *col = start;
+        }
+        let style = session_style(session);
+        let elapsed = format_elapsed(now_s.saturating_sub(session.last_event_ts));
+        let text = format!(" {} {elapsed}", session_label(session));
+        buf.push_str(&fg(style.r, style.g, style.b));
+        write_clipped(buf, col, end, &text);
+
+        state.click_regions.push(ClickRegion {
+            row: 1,
+            start_col: start,
+            end_col: end,
+            tab_index,
+            pane_id: session.pane_id,
+            focus_pane: true,
+        });
+    }
+}
+
+/// Session list for the sidebar layout: one row per Claude session below the
+/// header, each showing symbol, tab, cwd basename, tool and elapsed time.
+fn render_sidebar(state: &mut State, buf: &mut String, rows: usize, cols: usize) {
+    let now_s = unix_now();
+    let now_ms = unix_now_ms();
+    let bar_bg_str = bg(BAR_BG.0, BAR_BG.1, BAR_BG.2);
+    let focused = state.focused_pane_id();
+
+    let mut sessions: Vec<&SessionInfo> = state.sessions.values().collect();
+    match state.settings.sidebar_sort {
+        SidebarSort::Tab => sessions.sort_by_key(|s| (s.tab_index, s.pane_id)),
+        SidebarSort::Urgency => sessions.sort_by_key(|s| {
+            (std::cmp::Reverse(activity_priority(&s.activity)), s.last_event_ts)
+        }),
+    }
+    // While the settings menu occupies the header, leave the list blank
+    if state.view_mode != ViewMode::Normal {
+        sessions.clear();
+    }
+    let list_rows = rows.saturating_sub(1);
+    let overflow = sessions.len() > list_rows;
+    let shown = if overflow { list_rows.saturating_sub(1) } else { sessions.len() };
+
+    let mut regions = Vec::new();
+    for row in 1..rows {
+        let _ = write!(buf, "\x1b[{};1H", row + 1);
+        let mut col = 0;
+        let index = row - 1;
+        if index < shown {
+            let s = sessions[index];
+            let style = session_style(s);
+            let is_flash_bright = state
+                .flash_deadlines
+                .get(&s.pane_id)
+                .map(|&deadline| now_ms < deadline && (now_ms / 250).is_multiple_of(2))
+                .unwrap_or(false);
+            let row_bg = if is_flash_bright {
+                FLASH_BG_BRIGHT
+            } else if focused == Some(s.pane_id) {
+                TAB_BG_ACTIVE
+            } else if s.tab_index == state.active_tab_index {
+                TAB_BG_INACTIVE
+            } else {
+                BAR_BG
+            };
+            let row_bg_str = bg(row_bg.0, row_bg.1, row_bg.2);
+
+            // Right side: tool and elapsed time, kept whole when possible
+            let elapsed = format_elapsed(now_s.saturating_sub(s.last_event_ts));
+            let right = format!(" {} {elapsed} ", session_label(s));
+            let right_width = display_width(&right);
+            let left_limit = cols.saturating_sub(right_width).max(cols.min(4));
+
+            let _ = write!(buf, "{row_bg_str} ");
+            col += 1;
+            if let Some(icon) = agent_icon(&s.agent) {
+                let (r, g, b) = AGENT_ICON_COLOR;
+                let _ = write!(buf, "{}{icon}", fg(r, g, b));
+                col += display_width(&icon);
+            }
+            let _ = write!(buf, "{}{}", fg(style.r, style.g, style.b), style.symbol);
+            col += display_width(style.symbol);
+            if s.muted {
+                let (r, g, b) = AGENT_ICON_COLOR;
+                let _ = write!(buf, "{}{MUTED_MARK}", fg(r, g, b));
+                col += 1;
+            }
+            let tab_name = s.tab_name.as_deref().unwrap_or("?");
+            let _ = write!(buf, " {BOLD}{}", fg(255, 255, 255));
+            col += 1;
+            if write_clipped(buf, &mut col, left_limit, tab_name) {
+                let dir = s.cwd.as_deref().and_then(|c| c.rsplit('/').find(|p| !p.is_empty()));
+                if let Some(dir) = dir {
+                    let _ = write!(buf, "{RESET}{row_bg_str}{}", fg(120, 220, 220));
+                    if write_clipped(buf, &mut col, left_limit, " ") {
+                        write_clipped(buf, &mut col, left_limit, dir);
+                    }
+                }
+            }
+            let _ = write!(buf, "{RESET}{row_bg_str}");
+            if col + right_width <= cols {
+                let pad = cols - right_width - col;
+                let _ = write!(buf, "{:pad$}{}{right}", "", fg(165, 160, 180));
+                col = cols;
+            }
+            regions.push(ClickRegion {
+                row,
+                start_col: 0,
+                end_col: cols,
+                tab_index: s.tab_index.unwrap_or(0),
+                pane_id: s.pane_id,
+                focus_pane: true,
+            });
+        } else if index == shown && overflow {
+            let _ = write!(buf, "{bar_bg_str}{}", fg(165, 160, 180));
+            write_clipped(buf, &mut col, cols, &format!(" … +{} more", sessions.len() - shown));
+        } else if index == 0 && sessions.is_empty() && state.view_mode == ViewMode::Normal {
+            let _ = write!(buf, "{bar_bg_str}{}", fg(100, 100, 100));
+            write_clipped(buf, &mut col, cols, " no Claude sessions");
+        }
+        if col < cols {
+            let _ = write!(buf, "{bar_bg_str}{:width$}", "", width = cols - col);
+        }
+        let _ = write!(buf, "{RESET}");
+    }
+    state.click_regions.extend(regions);
+}
+
+/// Compact tab strip for narrow terminals: ` 1⚡ ` per Claude tab, ` 2 ` otherwise.
+fn render_tabs_compact(
+    state: &mut State,
+    buf: &mut String,
+    col: &mut usize,
+    cols: usize,
+    prefix_bg: Color,
+) {
+    let now_ms = unix_now_ms();
+
+    let mut tabs: Vec<&TabInfo> = state.tabs.iter().collect();
+    tabs.sort_by_key(|t| t.position);
+    let skipped = state.tab_scroll.min(tabs.len().saturating_sub(1));
+    tabs.drain(..skipped);
+
+    let count = tabs.len();
+    state.tab_scroll_max = skipped;
+    let mut prev_bg = prefix_bg;
+    for (i, tab) in tabs.into_iter().enumerate() {
+        let sessions = || {
+            state
+                .sessions
+                .values()
+                .filter(|s| s.tab_index == Some(tab.position))
+        };
+        let session = sessions().max_by_key(|s| activity_priority(&s.activity));
+        let number = (tab.position + 1).to_string();
+        let width = 1 + number.len() + usize::from(session.is_some()) + 1;
+        let arrows_needed = if prev_bg == prefix_bg { 1 } else { 2 };
+        if *col + arrows_needed + width + 1 > cols {
+            state.tab_scroll_max = skipped + count - i;
+            break;
+        }
+
+        let is_flash_bright = sessions().any(|s| {
+            state
+                .flash_deadlines
+                .get(&s.pane_id)
+                .map(|&deadline| now_ms < deadline && (now_ms / 250).is_multiple_of(2))
+                .unwrap_or(false)
+        });
+        let tab_bg = if is_flash_bright {
+            FLASH_BG_BRIGHT
+        } else if tab.active {
+            TAB_BG_ACTIVE
+        } else {
+            TAB_BG_INACTIVE
+        };
+
+        if prev_bg == prefix_bg {
+            arrow(buf, col, prev_bg, tab_bg);
+        } else {
+            arrow(buf, col, prev_bg, BAR_BG);
+            arrow(buf, col, BAR_BG, tab_bg);
+        }
+
+        let region_start = *col;
+        let num_fg = if is_flash_bright {
+            fg(255, 255, 80)
+        } else if tab.active {
+            fg(255, 255, 255)
+        } else {
+            fg(170, 165, 185)
+        };
+        let bold_str = if tab.active { BOLD } else { "" };
+        let _ = write!(buf, "{}{bold_str}{num_fg} {number}", bg(tab_bg.0, tab_bg.1, tab_bg.2));
+        if let Some(s) = session {
+            let style = session_style(s);
+            let _ = write!(buf, "{}{}", fg(style.r, style.g, style.b), style.symbol);
+        }
+        let _ = write!(buf, " {RESET}");
+        *col += width;
+
+        let waiting_session = sessions().find(|s| matches!(s.activity, Activity::Waiting));
+        state.click_regions.push(ClickRegion {
+            row: 0,
+            start_col: region_start,
+            end_col: *col,
+            tab_index: tab.position,
+            pane_id: waiting_session.map_or(0, |s| s.pane_id),
+            focus_pane: waiting_session.is_some(),
+        });
+
+        prev_bg = tab_bg;
+    }
+
+    arrow(buf, col, prev_bg, BAR_BG);
+}
+
 fn notify_mode_label(mode: NotifyMode) -> (&'static str, &'static str, String, String) {
     match mode {
         NotifyMode::Always => ("●", "Notify: always", fg(80, 200, 120), fg(255, 255, 255)),
@@ -471,102 +1366,424 @@ fn flash_mode_label(mode: FlashMode) -> (&'static str, &'static str, String, Str
     }
 }
 
-/// Render a three-state toggle and register its click region.
-/// Assumes the caller has already set the desired background color.
-fn render_tristate(
+/// A menu entry as shown in the settings row.
+struct MenuItem {
+    symbol: &'static str,
+    label: String,
+    sym_color: String,
+    label_color: String,
+}
+
+impl MenuItem {
+    fn width(&self) -> usize {
+        display_width(self.symbol) + 1 + display_width(&self.label)
+    }
+}
+
+fn tristate_item(
+    (symbol, label, sym_color, label_color): (&'static str, &'static str, String, String),
+) -> MenuItem {
+    MenuItem {
+        symbol,
+        label: label.to_string(),
+        sym_color,
+        label_color,
+    }
+}
+
+fn bool_item(enabled: bool, on: &str, off: &str) -> MenuItem {
+    let (symbol, sym_color, label_color) = if enabled {
+        ("●", fg(80, 200, 120), fg(255, 255, 255))
+    } else {
+        ("○", fg(100, 100, 100), fg(100, 100, 100))
+    };
+    MenuItem {
+        symbol,
+        label: if enabled { on } else { off }.to_string(),
+        sym_color,
+        label_color,
+    }
+}
+
+/// A setting with a numeric value that cycles through presets.
+fn value_item(label: String) -> MenuItem {
+    MenuItem {
+        symbol: "◆",
+        label,
+        sym_color: fg(80, 180, 255),
+        label_color: fg(255, 255, 255),
+    }
+}
+
+fn layout_mode_label(mode: LayoutMode) -> (&'static str, &'static str, String, String) {
+    match mode {
+        LayoutMode::Auto => ("◐", "Layout: auto", fg(80, 200, 120), fg(255, 255, 255)),
+        LayoutMode::Full => ("●", "Layout: full", fg(255, 200, 60), fg(255, 200, 60)),
+        LayoutMode::Compact => ("○", "Layout: compact", fg(255, 200, 60), fg(255, 200, 60)),
+        LayoutMode::Sidebar => ("◧", "Layout: sidebar", fg(255, 200, 60), fg(255, 200, 60)),
+    }
+}
+
+fn detail_row_label(mode: DetailRow) -> (&'static str, &'static str, String, String) {
+    match mode {
+        DetailRow::Session => ("●", "Detail row: session", fg(80, 200, 120), fg(255, 255, 255)),
+        DetailRow::Tabs => ("◐", "Detail row: tabs", fg(255, 200, 60), fg(255, 200, 60)),
+    }
+}
+
+fn setting_item(state: &State, key: SettingKey) -> MenuItem {
+    let settings = &state.settings;
+    match key {
+        SettingKey::Notifications => tristate_item(notify_mode_label(settings.notifications)),
+        SettingKey::Flash => tristate_item(flash_mode_label(settings.flash)),
+        SettingKey::ElapsedTime => {
+            bool_item(settings.elapsed_time, "Elapsed time: on", "Elapsed time: off")
+        }
+        SettingKey::ModeIndicator => {
+            bool_item(settings.mode_indicator, "Mode indicator: on", "Mode indicator: off")
+        }
+        SettingKey::Summary => bool_item(settings.summary, "Summary: on", "Summary: off"),
+        SettingKey::Clock => bool_item(settings.clock, "Clock: on", "Clock: off"),
+        SettingKey::SessionInSummary => bool_item(
+            settings.session_in_summary,
+            "Session name: in summary",
+            "Session name: in prefix",
+        ),
+        SettingKey::Layout => tristate_item(layout_mode_label(settings.layout)),
+        SettingKey::CompactWidth => {
+            value_item(format!("Compact below: {} cols", settings.compact_width))
+        }
+        SettingKey::DetailRow => tristate_item(detail_row_label(settings.detail_row)),
+        SettingKey::DoneTimeout => match settings.done_timeout {
+            0 => value_item("Done fades: never".to_string()),
+            secs => value_item(format!("Done fades: {}", format_elapsed(secs))),
+        },
+        SettingKey::ElapsedThreshold => value_item(format!(
+            "Elapsed after: {}",
+            format_elapsed(settings.elapsed_threshold)
+        )),
+        SettingKey::FlashDuration => value_item(format!(
+            "Flash length: {}",
+            format_elapsed(settings.flash_duration_ms / 1000)
+        )),
+        SettingKey::StallBash => stall_item("Bash stalls", settings.stall_bash),
+        SettingKey::StallThinking => stall_item("Thinking stalls", settings.stall_thinking),
+        SettingKey::StallAgent => stall_item("Agent stalls", settings.stall_agent),
+        SettingKey::HistoryButton => {
+            bool_item(settings.history_button, "Back button: on", "Back button: off")
+        }
+        SettingKey::Follow => bool_item(settings.follow, "Follow: on", "Follow: off"),
+        SettingKey::QuickApprove => {
+            bool_item(settings.quick_approve, "Quick approve: on", "Quick approve: off")
+        }
+        SettingKey::StallNotify => {
+            bool_item(settings.stall_notify, "Stall alerts: on", "Stall alerts: off")
+        }
+        SettingKey::Dnd => match dnd_remaining(state) {
+            Some(text) => MenuItem {
+                symbol: "☾",
+                label: format!("DND: {text}"),
+                sym_color: fg(DND_COLOR.0, DND_COLOR.1, DND_COLOR.2),
+                label_color: fg(255, 255, 255),
+            },
+            None => value_item("DND: off".to_string()),
+        },
+        SettingKey::ScrollAction => bool_item(
+            settings.scroll_action == ScrollAction::ScrollTabs,
+            "Wheel: scrolls tabs",
+            "Wheel: switches tab",
+        ),
+        SettingKey::LaunchTarget => bool_item(
+            settings.launch_target == LaunchTarget::Floating,
+            "+ opens: floating pane",
+            "+ opens: new tab",
+        ),
+        SettingKey::RightClick => value_item(
+            match settings.right_click {
+                ClickAction::Mute => "Right click: mute",
+                ClickAction::CloseTab => "Right click: close tab",
+                ClickAction::FocusUrgent => "Right click: focus urgent",
+            }
+            .to_string(),
+        ),
+        SettingKey::QuietHours => match settings.quiet_hours.as_str() {
+            "" => value_item("Quiet hours: off".to_string()),
+            hours => value_item(format!("Quiet hours: {hours}")),
+        },
+        SettingKey::OtherSessions => {
+            bool_item(settings.other_sessions, "Other sessions: on", "Other sessions: off")
+        }
+        SettingKey::SidebarSort => bool_item(
+            settings.sidebar_sort == SidebarSort::Urgency,
+            "Sidebar: by urgency",
+            "Sidebar: by tab",
+        ),
+    }
+}
+
+/// How long the snooze lasts: minutes or hours left, or the end time when
+/// that's tomorrow.
+fn dnd_remaining(state: &State) -> Option<String> {
+    let left = state.settings.dnd_until.checked_sub(unix_now()).filter(|&l| l > 0)?;
+    Some(if left > 3 * 3600 {
+        format!("until {}", format_clock(state.settings.dnd_until, state.utc_offset_secs))
+    } else if left < 60 {
+        "1m".to_string()
+    } else {
+        format_elapsed(left)
+    })
+}
+
+fn stall_item(name: &str, secs: u64) -> MenuItem {
+    match secs {
+        0 => value_item(format!("{name}: never")),
+        secs => value_item(format!("{name}: {}", format_elapsed(secs))),
+    }
+}
+
+fn info_item(state: &State, key: InfoKey) -> MenuItem {
+    let label = match key {
+        InfoKey::Protocol => match state.hook_protocol {
+            Some(v) => format!("Protocol: v{PROTOCOL_VERSION}, hook v{v}"),
+            None => format!("Protocol: v{PROTOCOL_VERSION}, no hook events yet"),
+        },
+        InfoKey::Rejected => format!("Rejected payloads: {}", state.rejected_payloads),
+        InfoKey::LastError => match state.payload_error {
+            Some(ref err) => format!("Last error: {err}"),
+            None => "Last error: none".to_string(),
+        },
+    };
+    let problem = match key {
+        InfoKey::Protocol => state.hook_protocol.is_some_and(|v| v != PROTOCOL_VERSION),
+        InfoKey::Rejected | InfoKey::LastError => state.rejected_payloads > 0,
+    };
+    MenuItem {
+        symbol: if problem { "⚠" } else { "·" },
+        label,
+        sym_color: if problem { fg(255, 60, 60) } else { fg(120, 115, 140) },
+        label_color: fg(220, 215, 230),
+    }
+}
+
+fn entry_item(state: &State, entry: MenuEntry) -> MenuItem {
+    match entry {
+        MenuEntry::Setting(key) => setting_item(state, key),
+        MenuEntry::Info(key) => info_item(state, key),
+        MenuEntry::Page(page) => MenuItem {
+            symbol: "▸",
+            label: page.title().to_string(),
+            sym_color: fg(180, 140, 255),
+            label_color: fg(255, 255, 255),
+        },
+    }
+}
+
+/// Render a menu entry, clipped at `limit`, and register its click region.
+/// Assumes the caller has already set the bar background color.
+fn render_item(
     buf: &mut String,
     col: &mut usize,
-    state_regions: &mut Vec<MenuClickRegion>,
-    key: SettingKey,
-    symbol: &str,
-    label: &str,
-    sym_color: &str,
-    label_color: &str,
+    limit: usize,
+    regions: &mut Vec<MenuClickRegion>,
+    (index, item): (usize, &MenuItem),
+    selected: bool,
 ) {
     let region_start = *col;
-    let width = display_width(symbol) + 1 + label.len();
-    *col += width;
+    if selected {
+        buf.push_str(&bg(TAB_BG_INACTIVE.0, TAB_BG_INACTIVE.1, TAB_BG_INACTIVE.2));
+    }
+    buf.push_str(&item.sym_color);
+    if write_clipped(buf, col, limit, item.symbol) {
+        buf.push_str(&item.label_color);
+        if write_clipped(buf, col, limit, " ") {
+            write_clipped(buf, col, limit, &item.label);
+        }
+    }
+    if selected {
+        buf.push_str(&bg(BAR_BG.0, BAR_BG.1, BAR_BG.2));
+    }
 
-    state_regions.push(MenuClickRegion {
+    regions.push(MenuClickRegion {
         start_col: region_start,
         end_col: *col,
-        action: MenuAction::ToggleSetting(key),
+        action: MenuAction::Activate(index),
     });
+}
 
-    let _ = write!(buf, "{sym_color}{symbol} {label_color}{label}");
+/// Write a clickable label (scroll arrows, back button) in a dim color.
+fn render_menu_button(
+    buf: &mut String,
+    col: &mut usize,
+    regions: &mut Vec<MenuClickRegion>,
+    text: &str,
+    action: MenuAction,
+) {
+    let start = *col;
+    let _ = write!(buf, "{}{text}", fg(165, 160, 180));
+    *col += display_width(text);
+    regions.push(MenuClickRegion {
+        start_col: start,
+        end_col: *col,
+        action,
+    });
 }
 
-fn render_settings_menu(state: &mut State, buf: &mut String, col: &mut usize) {
+/// Right-align the close button in the last two columns.
+fn render_close_button(state: &mut State, buf: &mut String, col: &mut usize, cols: usize) {
+    let close_start = cols.saturating_sub(2).max(*col);
+    if close_start + 1 > cols {
+        return;
+    }
+    let _ = write!(buf, "{:pad$}{}× ", "", fg(255, 60, 60), pad = close_start - *col);
+    *col = (close_start + 2).min(cols);
+
+    state.menu_click_regions.push(MenuClickRegion {
+        start_col: close_start,
+        end_col: close_start + 1,
+        action: MenuAction::CloseMenu,
+    });
+}
+
+/// Write the "◂ Title" back button on submenu pages.
+fn render_back_button(state: &mut State, buf: &mut String, col: &mut usize, label: &str) {
+    if state.menu_page.parent().is_some() {
+        render_menu_button(buf, col, &mut state.menu_click_regions, label, MenuAction::Back);
+        let _ = write!(buf, "  ");
+        *col += 2;
+    }
+}
+
+/// How many items starting at `scroll` fit between `start` and `end`,
+/// keeping room for the scroll arrows.
+fn visible_items(items: &[MenuItem], scroll: usize, start: usize, end: usize) -> usize {
+    let mut pos = start + if scroll > 0 { 2 } else { 0 };
+    let mut count = 0;
+    for (i, item) in items.iter().enumerate().skip(scroll) {
+        let sep = if i > scroll { 2 } else { 0 };
+        let more = if i + 1 < items.len() { 2 } else { 0 };
+        if pos + sep + item.width() + more > end {
+            break;
+        }
+        pos += sep + item.width();
+        count += 1;
+    }
+    count
+}
+
+fn render_settings_menu(state: &mut State, buf: &mut String, col: &mut usize, cols: usize) {
+    let items: Vec<MenuItem> = state
+        .menu_page
+        .entries()
+        .iter()
+        .map(|&entry| entry_item(state, entry))
+        .collect();
+    let end = cols.saturating_sub(3); // "  ×" is kept at the right edge
+
     // Leading space after arrow
     let _ = write!(buf, " ");
     *col += 1;
-
-    // --- Notifications (three-state) ---
+    let back_label = format!("◂ {}", state.menu_page.title());
+    render_back_button(state, buf, col, &back_label);
+
+    // Scroll so the selected entry is visible
+    state.menu_selected = state.menu_selected.min(items.len() - 1);
+    state.menu_scroll = state.menu_scroll.min(state.menu_selected);
+    while state.menu_scroll < state.menu_selected
+        && state.menu_scroll + visible_items(&items, state.menu_scroll, *col, end)
+            <= state.menu_selected
     {
-        let (symbol, label, sym_color, label_color) =
-            notify_mode_label(state.settings.notifications);
-        render_tristate(
-            buf, col, &mut state.menu_click_regions,
-            SettingKey::Notifications, symbol, label, &sym_color, &label_color,
-        );
+        state.menu_scroll += 1;
     }
+    let scroll = state.menu_scroll;
+    // Always show at least one (clipped) item
+    let visible = visible_items(&items, scroll, *col, end).max(1);
 
-    // --- Flash (three-state) ---
-    {
-        let _ = write!(buf, "  ");
-        *col += 2;
-        let (symbol, label, sym_color, label_color) =
-            flash_mode_label(state.settings.flash);
-        render_tristate(
-            buf, col, &mut state.menu_click_regions,
-            SettingKey::Flash, symbol, label, &sym_color, &label_color,
-        );
+    if scroll > 0 {
+        let prev = scroll.saturating_sub(visible);
+        render_menu_button(buf, col, &mut state.menu_click_regions, "‹ ", MenuAction::Scroll(prev));
+    }
+    let more = scroll + visible < items.len();
+    let limit = if more { end.saturating_sub(2) } else { end };
+    for (i, item) in items.iter().enumerate().skip(scroll).take(visible) {
+        if i > scroll {
+            let _ = write!(buf, "  ");
+            *col += 2;
+        }
+        let selected = i == state.menu_selected;
+        render_item(buf, col, limit, &mut state.menu_click_regions, (i, item), selected);
+    }
+    if more && *col + 2 <= cols {
+        let next = scroll + visible;
+        render_menu_button(buf, col, &mut state.menu_click_regions, " ›", MenuAction::Scroll(next));
     }
 
-    // --- Elapsed time (bool) ---
-    {
-        let _ = write!(buf, "  ");
+    render_close_button(state, buf, col, cols);
+}
+
+/// Directory picker of the launcher: the recent session directories, then
+/// wherever Zellij opens new panes.
+fn render_launcher(state: &mut State, buf: &mut String, col: &mut usize, cols: usize) {
+    let end = cols.saturating_sub(3); // "  ×" is kept at the right edge
+    let (r, g, b) = LAUNCH_COLOR;
+    let title = format!(" + {} in:", state.settings.launch_command);
+    let _ = write!(buf, "{}{BOLD}", fg(r, g, b));
+    write_clipped(buf, col, end, &title);
+    let _ = write!(buf, "{RESET}{}", bg(BAR_BG.0, BAR_BG.1, BAR_BG.2));
+
+    let mut choices: Vec<(String, MenuAction)> = state
+        .recent_cwds
+        .iter()
+        .enumerate()
+        .map(|(i, dir)| (clip_dir(dir), MenuAction::Launch(Some(i))))
+        .collect();
+    choices.push(("current dir".to_string(), MenuAction::Launch(None)));
+    for (label, action) in choices {
+        if *col + 2 + display_width(&label) > end {
+            break;
+        }
+        let _ = write!(buf, "  {}{label}", fg(255, 255, 255));
         *col += 2;
-        let enabled = state.settings.elapsed_time;
-        let (symbol, sym_color, label_color) = if enabled {
-            ("●", fg(80, 200, 120), fg(255, 255, 255))
-        } else {
-            ("○", fg(100, 100, 100), fg(100, 100, 100))
-        };
-        let label = if enabled { "Elapsed time: on" } else { "Elapsed time: off" };
-        render_tristate(
-            buf, col, &mut state.menu_click_regions,
-            SettingKey::ElapsedTime, symbol, label, &sym_color, &label_color,
-        );
+        state.menu_click_regions.push(MenuClickRegion {
+            start_col: *col,
+            end_col: *col + display_width(&label),
+            action,
+        });
+        *col += display_width(&label);
     }
 
-    // --- Mode indicator (bool) ---
-    {
-        let _ = write!(buf, "  ");
-        *col += 2;
-        let enabled = state.settings.mode_indicator;
-        let (symbol, sym_color, label_color) = if enabled {
-            ("●", fg(80, 200, 120), fg(255, 255, 255))
-        } else {
-            ("○", fg(100, 100, 100), fg(100, 100, 100))
-        };
-        let label = if enabled { "Mode indicator: on" } else { "Mode indicator: off" };
-        render_tristate(
-            buf, col, &mut state.menu_click_regions,
-            SettingKey::ModeIndicator, symbol, label, &sym_color, &label_color,
-        );
+    render_close_button(state, buf, col, cols);
+}
+
+/// A directory cut to `LAUNCH_DIR_WIDTH`, keeping its last components.
+fn clip_dir(dir: &str) -> String {
+    let count = dir.chars().count();
+    if count <= LAUNCH_DIR_WIDTH {
+        return dir.to_string();
     }
+    let tail: String = dir.chars().skip(count + 1 - LAUNCH_DIR_WIDTH).collect();
+    format!("…{tail}")
+}
 
-    // Close button
-    let _ = write!(buf, "  ");
-    *col += 2;
-    let close_start = *col;
-    let _ = write!(buf, "{}×", fg(255, 60, 60));
+/// Single-item settings view for the compact and sidebar layouts, with ‹ ›
+/// to step through the entries of the current page.
+fn render_settings_item(state: &mut State, buf: &mut String, col: &mut usize, cols: usize) {
+    let entries = state.menu_page.entries();
+    let len = entries.len();
+    let index = state.menu_selected.min(len - 1);
+    let item = entry_item(state, entries[index]);
+
+    let _ = write!(buf, " ");
     *col += 1;
+    render_back_button(state, buf, col, "◂");
+
+    let regions = &mut state.menu_click_regions;
+    render_menu_button(buf, col, regions, "‹ ", MenuAction::Scroll((index + len - 1) % len));
+    // Room for " ›" and the close button
+    let limit = cols.saturating_sub(5);
+    render_item(buf, col, limit, regions, (index, &item), false);
+    if *col + 2 <= cols {
+        render_menu_button(buf, col, regions, " ›", MenuAction::Scroll((index + 1) % len));
+    }
 
-    state.menu_click_regions.push(MenuClickRegion {
-        start_col: close_start,
-        end_col: *col,
-        action: MenuAction::CloseMenu,
-    });
+    render_close_button(state, buf, col, cols);
 }
@@ -1,5 +1,5 @@
 use serde::{Deserialize, Serialize};
-use std::collections::{BTreeMap, HashMap};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::time::{SystemTime, UNIX_EPOCH};
 use zellij_tile::prelude::*;
 
@@ -17,8 +17,6 @@ pub fn unix_now_ms() -> u64 {
         .unwrap_or(0)
 }
 
-pub const FLASH_DURATION_MS: u64 = 2000;
-
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum Activity {
     Init,
@@ -30,6 +28,31 @@ pub enum Activity {
     Done,
     AgentDone,
     Idle,
+    /// The Claude process ended without a SessionEnd; exit code when known
+    Exited(Option<i32>),
+}
+
+impl Activity {
+    /// Exited with a nonzero code (killed, crashed)
+    pub fn is_crash(&self) -> bool {
+        matches!(self, Self::Exited(Some(code)) if *code != 0)
+    }
+
+    /// Stable lowercase name, as used by `zellaude:query`
+    pub fn keyword(&self) -> &'static str {
+        match self {
+            Self::Init => "init",
+            Self::Thinking => "thinking",
+            Self::Tool(_) => "tool",
+            Self::Prompting => "prompting",
+            Self::Waiting => "waiting",
+            Self::Notification => "notification",
+            Self::Done => "done",
+            Self::AgentDone => "agent_done",
+            Self::Idle => "idle",
+            Self::Exited(_) => "exited",
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -43,26 +66,154 @@ pub struct SessionInfo {
     pub cwd: Option<String>,
     #[serde(default)]
     pub last_ts_ms: u64,
+    /// One-line summary of the running (or pending) tool's input
+    #[serde(default)]
+    pub tool_input: Option<String>,
+    /// Text of the most recent Notification event
+    #[serde(default)]
+    pub last_message: Option<String>,
+    /// No event for longer than the stall threshold of the current activity
+    #[serde(default)]
+    pub stalled: bool,
+    /// Which agent CLI runs in the pane
+    #[serde(default = "default_agent")]
+    pub agent: String,
+    /// No flash or notification for this session's permission requests
+    #[serde(default)]
+    pub muted: bool,
+    /// Finished while its pane wasn't focused, and its tab hasn't been
+    /// visited since; such a session doesn't fade to idle
+    #[serde(default)]
+    pub unseen: bool,
+}
+
+pub fn default_agent() -> String {
+    DEFAULT_AGENT.to_string()
+}
+
+pub const DEFAULT_AGENT: &str = "claude";
+
+impl SessionInfo {
+    /// Seconds without an event after which this session counts as stalled,
+    /// or `None` if its activity can't stall.
+    pub fn stall_threshold(&self, settings: &Settings) -> Option<u64> {
+        let secs = match &self.activity {
+            Activity::Tool(name) if name == "Bash" => settings.stall_bash,
+            Activity::Tool(name) if name == "Task" || name == "Agent" => settings.stall_agent,
+            Activity::Thinking => settings.stall_thinking,
+            _ => return None,
+        };
+        (secs > 0).then_some(secs)
+    }
+}
+
+/// A permission request waiting for an answer, in `State::permission_queue`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PermissionRequest {
+    pub pane_id: u32,
+    pub tool: Option<String>,
+    /// One-line summary of the tool input
+    pub input: Option<String>,
+    /// When the request arrived (ms)
+    pub ts_ms: u64,
+}
+
+/// State shared between instances over `zellaude:sync`. Older instances
+/// send the bare session map instead.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct SyncPayload {
+    pub sessions: BTreeMap<u32, SessionInfo>,
+    #[serde(default)]
+    pub permission_queue: Vec<PermissionRequest>,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct HookPayload {
+    /// Protocol version of the hook script; absent before versioning (0)
+    #[serde(default)]
+    pub v: u32,
     pub session_id: Option<String>,
     pub pane_id: u32,
     pub hook_event: String,
     pub tool_name: Option<String>,
     pub cwd: Option<String>,
+    pub tool_input: Option<String>,
+    pub message: Option<String>,
     pub zellij_session: Option<String>,
     pub term_program: Option<String>,
     pub ts_ms: Option<u64>,
+    /// Agent CLI that sent the event; absent means Claude Code
+    pub agent: Option<String>,
+}
+
+/// Agent-neutral hook events. Claude Code's hook names map onto these
+/// directly; adapters for other agents send the snake_case names.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AgentEvent {
+    SessionStart,
+    SessionEnd,
+    /// The user submitted a prompt
+    Prompt,
+    ToolStart,
+    ToolEnd,
+    /// The agent waits for the user to approve something
+    Permission,
+    /// Informational message from the agent
+    Notification,
+    /// The agent finished its turn
+    TurnEnd,
+    SubagentEnd,
+}
+
+impl AgentEvent {
+    pub fn parse(name: &str) -> Option<Self> {
+        Some(match name {
+            "SessionStart" | "session_start" => Self::SessionStart,
+            "SessionEnd" | "session_end" => Self::SessionEnd,
+            "UserPromptSubmit" | "prompt" => Self::Prompt,
+            "PreToolUse" | "tool_start" => Self::ToolStart,
+            "PostToolUse" | "PostToolUseFailure" | "tool_end" => Self::ToolEnd,
+            "PermissionRequest" | "permission" => Self::Permission,
+            "Notification" | "notification" => Self::Notification,
+            "Stop" | "turn_end" => Self::TurnEnd,
+            "SubagentStop" | "subagent_end" => Self::SubagentEnd,
+            _ => return None,
+        })
+    }
+}
+
+/// Aggregate bucket a session counts towards in the right-aligned summary.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SummaryBucket {
+    Waiting,
+    Tool,
+    Thinking,
+    Done,
+}
+
+impl SummaryBucket {
+    pub const ALL: [SummaryBucket; 4] = [Self::Waiting, Self::Tool, Self::Thinking, Self::Done];
+
+    pub fn of(activity: &Activity) -> Option<Self> {
+        match activity {
+            Activity::Waiting => Some(Self::Waiting),
+            Activity::Tool(_) => Some(Self::Tool),
+            Activity::Thinking | Activity::Prompting => Some(Self::Thinking),
+            Activity::Done | Activity::AgentDone => Some(Self::Done),
+            _ => None,
+        }
+    }
 }
 
+#[derive(Clone)]
 pub struct ClickRegion {
+    pub row: usize,
     pub start_col: usize,
     pub end_col: usize,
     pub tab_index: usize,
     pub pane_id: u32,
-    pub is_waiting: bool,
+    /// Focus `pane_id` on click instead of just switching to the tab
+    pub focus_pane: bool,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Default)]
@@ -101,24 +252,515 @@ impl FlashMode {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Default)]
+pub enum LayoutMode {
+    /// Sidebar in panes taller than two rows, otherwise compact below
+    /// `compact_width` columns and full above
+    #[default]
+    Auto,
+    Full,
+    Compact,
+    /// One row per Claude session, for a tall pane beside the terminals
+    Sidebar,
+}
+
+impl LayoutMode {
+    pub fn cycle(self) -> Self {
+        match self {
+            Self::Auto => Self::Full,
+            Self::Full => Self::Compact,
+            Self::Compact => Self::Sidebar,
+            Self::Sidebar => Self::Auto,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Default)]
+pub enum SidebarSort {
+    #[default]
+    Tab,
+    Urgency,
+}
+
+impl SidebarSort {
+    pub fn cycle(self) -> Self {
+        match self {
+            Self::Tab => Self::Urgency,
+            Self::Urgency => Self::Tab,
+        }
+    }
+}
+
+/// What scrolling the mouse wheel over the bar does.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Default)]
+pub enum ScrollAction {
+    /// Go to the previous / next tab
+    #[default]
+    SwitchTab,
+    /// Scroll the tab list when it doesn't fit
+    ScrollTabs,
+}
+
+impl ScrollAction {
+    pub fn cycle(self) -> Self {
+        match self {
+            Self::SwitchTab => Self::ScrollTabs,
+            Self::ScrollTabs => Self::SwitchTab,
+        }
+    }
+}
+
+/// What a right click on a tab (or sidebar row) does.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Default)]
+pub enum ClickAction {
+    #[default]
+    Mute,
+    CloseTab,
+    /// Focus the most urgent session, wherever it is
+    FocusUrgent,
+}
+
+impl ClickAction {
+    pub fn cycle(self) -> Self {
+        match self {
+            Self::Mute => Self::CloseTab,
+            Self::CloseTab => Self::FocusUrgent,
+            Self::FocusUrgent => Self::Mute,
+        }
+    }
+}
+
+/// Where the `+` button starts a new session.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Default)]
+pub enum LaunchTarget {
+    #[default]
+    Tab,
+    Floating,
+}
+
+impl LaunchTarget {
+    pub fn cycle(self) -> Self {
+        match self {
+            Self::Tab => Self::Floating,
+            Self::Floating => Self::Tab,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
 pub struct Settings {
+    /// Schema version of the settings file (see `config::migrate`)
+    pub version: u32,
     pub notifications: NotifyMode,
     pub flash: FlashMode,
     pub elapsed_time: bool,
     pub mode_indicator: bool,
+    pub summary: bool,
+    pub clock: bool,
+    /// Show the Zellij session name in the summary instead of the prefix
+    pub session_in_summary: bool,
+    pub layout: LayoutMode,
+    /// Width below which `LayoutMode::Auto` switches to the compact layout
+    pub compact_width: usize,
+    pub detail_row: DetailRow,
+    pub sidebar_sort: SidebarSort,
+    /// Seconds before Done fades to Idle (0 = never)
+    pub done_timeout: u64,
+    /// Seconds in one state before the elapsed time is shown
+    pub elapsed_threshold: u64,
+    pub flash_duration_ms: u64,
+    /// Seconds a Bash command may run before the session counts as stalled (0 = never)
+    pub stall_bash: u64,
+    /// Seconds of thinking without any event before the session counts as stalled
+    pub stall_thinking: u64,
+    /// Seconds a subagent may run before the session counts as stalled
+    pub stall_agent: u64,
+    /// Desktop notification when a session stalls
+    pub stall_notify: bool,
+    /// Badge with the activity in other Zellij sessions
+    pub other_sessions: bool,
+    /// Do not disturb until this unix time (0 = not snoozed). Also read by
+    /// the hook script, which skips bells and notifications meanwhile.
+    pub dnd_until: u64,
+    /// Daily do-not-disturb window in local time, e.g. `22:00-07:00` ("" = none)
+    pub quiet_hours: String,
+    pub scroll_action: ScrollAction,
+    pub right_click: ClickAction,
+    /// Command the `+` button runs (through `sh -c`)
+    pub launch_command: String,
+    /// Directory for new sessions ("" = pick from recent ones)
+    pub launch_cwd: String,
+    pub launch_target: LaunchTarget,
+    /// ✓ / ✗ buttons that answer a waiting permission prompt from the bar
+    pub quick_approve: bool,
+    /// Focus new permission requests when the user looks idle
+    pub follow: bool,
+    /// ⟲ button by the prefix that goes back in the focus history
+    pub history_button: bool,
 }
 
 impl Default for Settings {
     fn default() -> Self {
         Self {
+            version: crate::config::CONFIG_VERSION,
             notifications: NotifyMode::Always,
             flash: FlashMode::Once,
             elapsed_time: true,
             mode_indicator: true,
+            summary: false,
+            clock: false,
+            session_in_summary: false,
+            layout: LayoutMode::Auto,
+            compact_width: 60,
+            detail_row: DetailRow::Session,
+            sidebar_sort: SidebarSort::Tab,
+            done_timeout: 30,
+            elapsed_threshold: 30,
+            flash_duration_ms: 2000,
+            stall_bash: 600,
+            stall_thinking: 300,
+            stall_agent: 1800,
+            stall_notify: false,
+            other_sessions: true,
+            dnd_until: 0,
+            quiet_hours: String::new(),
+            scroll_action: ScrollAction::SwitchTab,
+            right_click: ClickAction::Mute,
+            launch_command: "claude".to_string(),
+            launch_cwd: String::new(),
+            launch_target: LaunchTarget::Tab,
+            quick_approve: false,
+            follow: false,
+            history_button: false,
+        }
+    }
+}
+
+/// What the second row shows when the plugin pane is two rows tall.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Default)]
+pub enum DetailRow {
+    /// Details of the focused (or most urgent) session across the full width
+    #[default]
+    Session,
+    /// Short per-tab details under each tab segment
+    Tabs,
+}
+
+impl DetailRow {
+    pub fn cycle(self) -> Self {
+        match self {
+            Self::Session => Self::Tabs,
+            Self::Tabs => Self::Session,
+        }
+    }
+}
+
+impl Settings {
+    /// Advance a setting to its next value, as a click in the menu does.
+    /// The current time is needed to step through the snooze presets.
+    pub fn toggle(&mut self, key: SettingKey, now: u64, utc_offset_secs: i64) {
+        match key {
+            SettingKey::Notifications => self.notifications = self.notifications.cycle(),
+            SettingKey::Flash => self.flash = self.flash.cycle(),
+            SettingKey::ElapsedTime => self.elapsed_time = !self.elapsed_time,
+            SettingKey::ModeIndicator => self.mode_indicator = !self.mode_indicator,
+            SettingKey::Summary => self.summary = !self.summary,
+            SettingKey::Clock => self.clock = !self.clock,
+            SettingKey::Layout => self.layout = self.layout.cycle(),
+            SettingKey::DetailRow => self.detail_row = self.detail_row.cycle(),
+            SettingKey::SidebarSort => self.sidebar_sort = self.sidebar_sort.cycle(),
+            SettingKey::SessionInSummary => self.session_in_summary = !self.session_in_summary,
+            SettingKey::CompactWidth => {
+                self.compact_width = next_preset(&COMPACT_WIDTH_PRESETS, self.compact_width)
+            }
+            SettingKey::DoneTimeout => {
+                self.done_timeout = next_preset(&DONE_TIMEOUT_PRESETS, self.done_timeout)
+            }
+            SettingKey::ElapsedThreshold => {
+                self.elapsed_threshold =
+                    next_preset(&ELAPSED_THRESHOLD_PRESETS, self.elapsed_threshold)
+            }
+            SettingKey::FlashDuration => {
+                self.flash_duration_ms =
+                    next_preset(&FLASH_DURATION_PRESETS, self.flash_duration_ms)
+            }
+            SettingKey::StallBash => self.stall_bash = next_preset(&STALL_PRESETS, self.stall_bash),
+            SettingKey::StallThinking => {
+                self.stall_thinking = next_preset(&STALL_PRESETS, self.stall_thinking)
+            }
+            SettingKey::StallAgent => {
+                self.stall_agent = next_preset(&STALL_PRESETS, self.stall_agent)
+            }
+            SettingKey::StallNotify => self.stall_notify = !self.stall_notify,
+            SettingKey::OtherSessions => self.other_sessions = !self.other_sessions,
+            SettingKey::Dnd => {
+                // off → 15m → 1h → tomorrow morning → off. A morning deadline
+                // (picked before or after midnight) always steps to off.
+                let tomorrow = dnd_tomorrow(now, utc_offset_secs);
+                let morning = next_local_time(now, utc_offset_secs, DND_MORNING);
+                self.dnd_until = match self.dnd_until.saturating_sub(now) {
+                    0 => now + 15 * 60,
+                    _ if self.dnd_until == tomorrow || self.dnd_until == morning => 0,
+                    1..=900 => now + 3600,
+                    _ => tomorrow,
+                };
+            }
+            SettingKey::ScrollAction => self.scroll_action = self.scroll_action.cycle(),
+            SettingKey::RightClick => self.right_click = self.right_click.cycle(),
+            SettingKey::LaunchTarget => self.launch_target = self.launch_target.cycle(),
+            SettingKey::QuickApprove => self.quick_approve = !self.quick_approve,
+            SettingKey::Follow => self.follow = !self.follow,
+            SettingKey::HistoryButton => self.history_button = !self.history_button,
+            SettingKey::QuietHours => {
+                let i = QUIET_HOURS_PRESETS
+                    .iter()
+                    .position(|&p| p == self.quiet_hours)
+                    .map_or(0, |i| i + 1);
+                self.quiet_hours = QUIET_HOURS_PRESETS[i % QUIET_HOURS_PRESETS.len()].to_string();
+            }
+        }
+    }
+
+    /// Set a setting by its JSON field name from a string value, as given in
+    /// the layout's plugin configuration.
+    pub fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+        let value = value.trim();
+        match key {
+            "notifications" => {
+                self.notifications = match value.to_ascii_lowercase().as_str() {
+                    "always" => NotifyMode::Always,
+                    "unfocused" => NotifyMode::Unfocused,
+                    "never" | "off" => NotifyMode::Never,
+                    _ => return Err(format!("invalid notifications mode: {value}")),
+                }
+            }
+            "flash" => {
+                self.flash = match value.to_ascii_lowercase().as_str() {
+                    "once" | "brief" => FlashMode::Once,
+                    "persist" => FlashMode::Persist,
+                    "off" => FlashMode::Off,
+                    _ => return Err(format!("invalid flash mode: {value}")),
+                }
+            }
+            "elapsed_time" => self.elapsed_time = parse_bool(value)?,
+            "mode_indicator" => self.mode_indicator = parse_bool(value)?,
+            "summary" => self.summary = parse_bool(value)?,
+            "clock" => self.clock = parse_bool(value)?,
+            "session_in_summary" => self.session_in_summary = parse_bool(value)?,
+            "layout" => {
+                self.layout = match value.to_ascii_lowercase().as_str() {
+                    "auto" => LayoutMode::Auto,
+                    "full" => LayoutMode::Full,
+                    "compact" => LayoutMode::Compact,
+                    "sidebar" => LayoutMode::Sidebar,
+                    _ => return Err(format!("invalid layout: {value}")),
+                }
+            }
+            "compact_width" => {
+                self.compact_width = value
+                    .parse()
+                    .map_err(|_| format!("invalid compact_width: {value}"))?
+            }
+            "detail_row" => {
+                self.detail_row = match value.to_ascii_lowercase().as_str() {
+                    "session" => DetailRow::Session,
+                    "tabs" => DetailRow::Tabs,
+                    _ => return Err(format!("invalid detail_row: {value}")),
+                }
+            }
+            "sidebar_sort" => {
+                self.sidebar_sort = match value.to_ascii_lowercase().as_str() {
+                    "tab" => SidebarSort::Tab,
+                    "urgency" => SidebarSort::Urgency,
+                    _ => return Err(format!("invalid sidebar_sort: {value}")),
+                }
+            }
+            "done_timeout" => self.done_timeout = parse_duration_secs(value)?,
+            "elapsed_threshold" => self.elapsed_threshold = parse_duration_secs(value)?,
+            "flash_duration_ms" => {
+                self.flash_duration_ms = match value.parse() {
+                    Ok(ms) => ms,
+                    Err(_) => parse_duration_ms(value)?,
+                }
+            }
+            "flash_duration" => self.flash_duration_ms = parse_duration_ms(value)?,
+            "stall_bash" => self.stall_bash = parse_duration_secs(value)?,
+            "stall_thinking" => self.stall_thinking = parse_duration_secs(value)?,
+            "stall_agent" => self.stall_agent = parse_duration_secs(value)?,
+            "stall_notify" => self.stall_notify = parse_bool(value)?,
+            "other_sessions" => self.other_sessions = parse_bool(value)?,
+            "quick_approve" => self.quick_approve = parse_bool(value)?,
+            "follow" => self.follow = parse_bool(value)?,
+            "history_button" => self.history_button = parse_bool(value)?,
+            "scroll_action" => {
+                self.scroll_action = match value.to_ascii_lowercase().as_str() {
+                    "switchtab" | "switch_tab" | "switch" => ScrollAction::SwitchTab,
+                    "scrolltabs" | "scroll_tabs" | "scroll" => ScrollAction::ScrollTabs,
+                    _ => return Err(format!("invalid scroll_action: {value}")),
+                }
+            }
+            "right_click" => {
+                self.right_click = match value.to_ascii_lowercase().as_str() {
+                    "mute" => ClickAction::Mute,
+                    "closetab" | "close_tab" | "close" => ClickAction::CloseTab,
+                    "focusurgent" | "focus_urgent" | "urgent" => ClickAction::FocusUrgent,
+                    _ => return Err(format!("invalid right_click: {value}")),
+                }
+            }
+            "launch_command" => {
+                if value.trim().is_empty() {
+                    return Err("launch_command can't be empty".into());
+                }
+                self.launch_command = value.trim().to_string();
+            }
+            "launch_cwd" => self.launch_cwd = value.trim().to_string(),
+            "launch_target" => {
+                self.launch_target = match value.to_ascii_lowercase().as_str() {
+                    "tab" => LaunchTarget::Tab,
+                    "floating" | "float" => LaunchTarget::Floating,
+                    _ => return Err(format!("invalid launch_target: {value}")),
+                }
+            }
+            "dnd_until" => {
+                self.dnd_until = value
+                    .parse()
+                    .map_err(|_| format!("invalid dnd_until: {value}"))?
+            }
+            "quiet_hours" => {
+                let value = if value.eq_ignore_ascii_case("off") { "" } else { value };
+                parse_quiet_hours(value)?;
+                self.quiet_hours = value.to_string();
+            }
+            _ => return Err(format!("unknown setting: {key}")),
+        }
+        Ok(())
+    }
+
+    /// Snoozed, or inside the quiet hours.
+    pub fn dnd_active(&self, now: u64, utc_offset_secs: i64) -> bool {
+        self.dnd_until > now || self.in_quiet_hours(now, utc_offset_secs)
+    }
+
+    pub fn in_quiet_hours(&self, now: u64, utc_offset_secs: i64) -> bool {
+        let Ok(Some((start, end))) = parse_quiet_hours(&self.quiet_hours) else {
+            return false;
+        };
+        let minute = local_secs_of_day(now, utc_offset_secs) / 60;
+        if start <= end {
+            (start..end).contains(&minute)
+        } else {
+            minute >= start || minute < end
         }
     }
+
+    /// Resolve `LayoutMode::Auto` for a pane of the given size.
+    pub fn effective_layout(&self, rows: usize, cols: usize) -> LayoutMode {
+        match self.layout {
+            LayoutMode::Auto if rows > 2 => LayoutMode::Sidebar,
+            LayoutMode::Auto if cols < self.compact_width => LayoutMode::Compact,
+            LayoutMode::Auto => LayoutMode::Full,
+            layout => layout,
+        }
+    }
+}
+
+pub const COMPACT_WIDTH_PRESETS: [usize; 4] = [40, 60, 80, 100];
+/// 0 ("never") comes last so the cycle goes from shortest to longest
+pub const DONE_TIMEOUT_PRESETS: [u64; 5] = [30, 120, 600, 3600, 0];
+pub const ELAPSED_THRESHOLD_PRESETS: [u64; 5] = [0, 10, 30, 60, 300];
+pub const FLASH_DURATION_PRESETS: [u64; 4] = [1000, 2000, 5000, 10_000];
+pub const STALL_PRESETS: [u64; 6] = [60, 300, 600, 1800, 3600, 0];
+
+pub const QUIET_HOURS_PRESETS: [&str; 4] = ["", "22:00-07:00", "20:00-08:00", "00:00-08:00"];
+/// "Until tomorrow" snoozes end at this local time (seconds after midnight)
+pub const DND_MORNING: u64 = 8 * 3600;
+
+fn local_secs_of_day(now: u64, utc_offset_secs: i64) -> u64 {
+    (now as i64 + utc_offset_secs).rem_euclid(86_400) as u64
+}
+
+/// Next unix time at which the local clock reads `secs_of_day`.
+pub fn next_local_time(now: u64, utc_offset_secs: i64, secs_of_day: u64) -> u64 {
+    let today = local_secs_of_day(now, utc_offset_secs);
+    let wait = (secs_of_day + 86_400 - today) % 86_400;
+    now + if wait == 0 { 86_400 } else { wait }
+}
+
+/// End of an "until tomorrow" snooze: the morning after the next midnight,
+/// so picking it at 01:00 still lasts until the following day.
+pub fn dnd_tomorrow(now: u64, utc_offset_secs: i64) -> u64 {
+    next_local_time(now, utc_offset_secs, 0) + DND_MORNING
+}
+
+/// Parse `HH:MM-HH:MM` into minutes after midnight; empty means no window.
+pub fn parse_quiet_hours(value: &str) -> Result<Option<(u64, u64)>, String> {
+    if value.is_empty() {
+        return Ok(None);
+    }
+    let minutes = |hm: &str| -> Option<u64> {
+        let (h, m) = hm.trim().split_once(':')?;
+        let (h, m): (u64, u64) = (h.parse().ok()?, m.parse().ok()?);
+        (h < 24 && m < 60).then_some(h * 60 + m)
+    };
+    value
+        .split_once('-')
+        .and_then(|(start, end)| Some((minutes(start)?, minutes(end)?)))
+        .map(Some)
+        .ok_or_else(|| format!("invalid quiet_hours (expected HH:MM-HH:MM): {value}"))
+}
+
+/// The preset following `current`, wrapping around (values off the list
+/// snap to the first preset above them).
+pub fn next_preset<T: Copy + PartialOrd>(presets: &[T], current: T) -> T {
+    match presets.iter().position(|&p| p == current) {
+        Some(i) => presets[(i + 1) % presets.len()],
+        None => presets
+            .iter()
+            .copied()
+            .find(|&p| p > current)
+            .unwrap_or(presets[0]),
+    }
+}
+
+fn parse_bool(value: &str) -> Result<bool, String> {
+    match value.to_ascii_lowercase().as_str() {
+        "true" | "on" | "yes" | "1" => Ok(true),
+        "false" | "off" | "no" | "0" => Ok(false),
+        _ => Err(format!("invalid boolean: {value}")),
+    }
+}
+
+/// Parse a duration like `500ms`, `30s`, `5m`, `2h` or `never` (0) into
+/// milliseconds. A bare number is taken as seconds.
+pub fn parse_duration_ms(value: &str) -> Result<u64, String> {
+    let value = value.to_ascii_lowercase();
+    if value == "never" || value == "off" {
+        return Ok(0);
+    }
+    let (digits, unit_ms) = if let Some(d) = value.strip_suffix("ms") {
+        (d, 1)
+    } else if let Some(d) = value.strip_suffix('s') {
+        (d, 1000)
+    } else if let Some(d) = value.strip_suffix('m') {
+        (d, 60_000)
+    } else if let Some(d) = value.strip_suffix('h') {
+        (d, 3_600_000)
+    } else {
+        (value.as_str(), 1000)
+    };
+    digits
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .and_then(|n| n.checked_mul(unit_ms))
+        .ok_or_else(|| format!("invalid duration: {value}"))
+}
+
+/// Like [`parse_duration_ms`], in whole seconds. Sub-second values round up,
+/// so `500ms` doesn't turn into `never`.
+pub fn parse_duration_secs(value: &str) -> Result<u64, String> {
+    parse_duration_ms(value).map(|ms| ms.div_ceil(1000))
 }
 
 #[derive(Default, PartialEq)]
@@ -126,19 +768,200 @@ pub enum ViewMode {
     #[default]
     Normal,
     Settings,
+    /// Picking the directory for a new session
+    Launcher,
 }
 
-#[derive(Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum SettingKey {
     Notifications,
     Flash,
     ElapsedTime,
     ModeIndicator,
+    Summary,
+    Clock,
+    Layout,
+    DetailRow,
+    SidebarSort,
+    SessionInSummary,
+    CompactWidth,
+    DoneTimeout,
+    ElapsedThreshold,
+    FlashDuration,
+    StallBash,
+    StallThinking,
+    StallAgent,
+    StallNotify,
+    OtherSessions,
+    Dnd,
+    QuietHours,
+    ScrollAction,
+    RightClick,
+    LaunchTarget,
+    QuickApprove,
+    Follow,
+    HistoryButton,
+}
+
+impl SettingKey {
+    /// Look up a setting by its JSON field name.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "notifications" => Self::Notifications,
+            "flash" => Self::Flash,
+            "elapsed_time" => Self::ElapsedTime,
+            "mode_indicator" => Self::ModeIndicator,
+            "summary" => Self::Summary,
+            "clock" => Self::Clock,
+            "layout" => Self::Layout,
+            "detail_row" => Self::DetailRow,
+            "sidebar_sort" => Self::SidebarSort,
+            "session_in_summary" => Self::SessionInSummary,
+            "compact_width" => Self::CompactWidth,
+            "done_timeout" => Self::DoneTimeout,
+            "elapsed_threshold" => Self::ElapsedThreshold,
+            "flash_duration_ms" | "flash_duration" => Self::FlashDuration,
+            "stall_bash" => Self::StallBash,
+            "stall_thinking" => Self::StallThinking,
+            "stall_agent" => Self::StallAgent,
+            "stall_notify" => Self::StallNotify,
+            "other_sessions" => Self::OtherSessions,
+            "dnd_until" | "dnd" => Self::Dnd,
+            "quiet_hours" => Self::QuietHours,
+            "scroll_action" => Self::ScrollAction,
+            "right_click" => Self::RightClick,
+            "launch_target" => Self::LaunchTarget,
+            "quick_approve" => Self::QuickApprove,
+            "follow" => Self::Follow,
+            "history_button" => Self::HistoryButton,
+            _ => return None,
+        })
+    }
+}
+
+/// A page of the settings menu. The root page links to the others.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MenuPage {
+    #[default]
+    Root,
+    Alerts,
+    Display,
+    Layout,
+    Timing,
+    Stalls,
+    Mouse,
+    Diagnostics,
 }
 
+#[derive(Debug, Clone, Copy)]
+pub enum MenuEntry {
+    Setting(SettingKey),
+    Page(MenuPage),
+    /// Read-only status line
+    Info(InfoKey),
+}
+
+/// Status shown on the diagnostics page.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InfoKey {
+    /// Protocol versions of the plugin and the last hook heard from
+    Protocol,
+    /// Count of hook payloads rejected (click to reset)
+    Rejected,
+    LastError,
+}
+
+impl MenuPage {
+    pub fn title(self) -> &'static str {
+        match self {
+            Self::Root => "Settings",
+            Self::Alerts => "Alerts",
+            Self::Display => "Display",
+            Self::Layout => "Layout",
+            Self::Timing => "Timing",
+            Self::Stalls => "Stalls",
+            Self::Mouse => "Mouse",
+            Self::Diagnostics => "Diagnostics",
+        }
+    }
+
+    pub fn parent(self) -> Option<MenuPage> {
+        match self {
+            Self::Root => None,
+            _ => Some(Self::Root),
+        }
+    }
+
+    pub fn entries(self) -> &'static [MenuEntry] {
+        use MenuEntry::{Info, Page, Setting};
+        match self {
+            Self::Root => &[
+                Page(Self::Alerts),
+                Page(Self::Display),
+                Page(Self::Layout),
+                Page(Self::Timing),
+                Page(Self::Stalls),
+                Page(Self::Mouse),
+                Page(Self::Diagnostics),
+            ],
+            Self::Alerts => &[
+                Setting(SettingKey::Notifications),
+                Setting(SettingKey::Flash),
+                Setting(SettingKey::Dnd),
+                Setting(SettingKey::QuietHours),
+                Setting(SettingKey::QuickApprove),
+                Setting(SettingKey::Follow),
+            ],
+            Self::Display => &[
+                Setting(SettingKey::ElapsedTime),
+                Setting(SettingKey::ModeIndicator),
+                Setting(SettingKey::Summary),
+                Setting(SettingKey::Clock),
+                Setting(SettingKey::SessionInSummary),
+                Setting(SettingKey::OtherSessions),
+                Setting(SettingKey::LaunchTarget),
+            ],
+            Self::Layout => &[
+                Setting(SettingKey::Layout),
+                Setting(SettingKey::CompactWidth),
+                Setting(SettingKey::DetailRow),
+                Setting(SettingKey::SidebarSort),
+            ],
+            Self::Timing => &[
+                Setting(SettingKey::DoneTimeout),
+                Setting(SettingKey::ElapsedThreshold),
+                Setting(SettingKey::FlashDuration),
+            ],
+            Self::Stalls => &[
+                Setting(SettingKey::StallBash),
+                Setting(SettingKey::StallThinking),
+                Setting(SettingKey::StallAgent),
+                Setting(SettingKey::StallNotify),
+            ],
+            Self::Mouse => &[
+                Setting(SettingKey::ScrollAction),
+                Setting(SettingKey::RightClick),
+                Setting(SettingKey::HistoryButton),
+            ],
+            Self::Diagnostics => &[
+                Info(InfoKey::Protocol),
+                Info(InfoKey::Rejected),
+                Info(InfoKey::LastError),
+            ],
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
 pub enum MenuAction {
-    ToggleSetting(SettingKey),
+    /// Toggle the setting or open the submenu at this index of the current page
+    Activate(usize),
+    /// Scroll the menu so this entry is the first one shown
+    Scroll(usize),
+    Back,
     CloseMenu,
+    /// Start a session in this entry of `recent_cwds` (None = Zellij's default cwd)
+    Launch(Option<usize>),
 }
 
 pub struct MenuClickRegion {
@@ -147,9 +970,26 @@ pub struct MenuClickRegion {
     pub action: MenuAction,
 }
 
+/// A ✓ or ✗ button answering the permission prompt in `pane_id`.
+pub struct ApproveClickRegion {
+    pub row: usize,
+    pub start_col: usize,
+    pub end_col: usize,
+    pub pane_id: u32,
+    pub allow: bool,
+}
+
+pub struct SummaryClickRegion {
+    pub start_col: usize,
+    pub end_col: usize,
+    pub bucket: SummaryBucket,
+}
+
 #[derive(Default)]
 pub struct State {
     pub sessions: BTreeMap<u32, SessionInfo>,
+    /// Pending permission requests, oldest first
+    pub permission_queue: Vec<PermissionRequest>,
     pub pane_to_tab: HashMap<u32, (usize, String)>,
     pub tabs: Vec<TabInfo>,
     pub pane_manifest: Option<PaneManifest>,
@@ -160,10 +1000,104 @@ pub struct State {
     pub zellij_session_name: Option<String>,
     pub term_program: Option<String>,
     pub input_mode: InputMode,
+    /// Settings in effect: the file's, with the layout's overrides on top
     pub settings: Settings,
+    /// Settings as stored in the file, without the layout's overrides. This
+    /// is what gets saved and broadcast.
+    pub file_settings: Settings,
     pub view_mode: ViewMode,
     pub prefix_click_region: Option<(usize, usize)>,
+    /// Where the other-sessions badge was drawn on the first row
+    pub overview_click_region: Option<(usize, usize)>,
+    /// Fresh snapshots published by the other Zellij sessions
+    pub other_sessions: Vec<crate::overview::Snapshot>,
+    pub last_overview_sync: u64,
+    /// Last keyboard or mouse input anywhere in Zellij (ms)
+    pub last_input_ms: u64,
+    /// Tab that follow mode moved away from
+    pub jump_back: Option<usize>,
+    /// Command waiting for the tab opened to launch it: the tab count before
+    /// and when to give up (unix secs)
+    pub pending_launch: Option<(CommandToRun, usize, u64)>,
+    /// Focused (tab, pane) over time, for back / forward
+    pub focus_history: Vec<(usize, u32)>,
+    /// Entry of `focus_history` that is focused now
+    pub history_pos: usize,
+    /// Where the ⟲ (back) button was drawn on the first row
+    pub history_click_region: Option<(usize, usize)>,
+    /// Where the ↩ (jump back) button was drawn on the first row
+    pub jump_back_click_region: Option<(usize, usize)>,
+    /// Where the pending-requests badge was drawn on the first row
+    pub queue_click_region: Option<(usize, usize)>,
+    /// Where the `+` button was drawn on the first row
+    pub launch_click_region: Option<(usize, usize)>,
+    /// Session directories, most recent first, for the launcher
+    pub recent_cwds: Vec<String>,
     pub menu_click_regions: Vec<MenuClickRegion>,
+    pub menu_page: MenuPage,
+    /// Highlighted entry of the current page (keyboard control)
+    pub menu_selected: usize,
+    /// First entry shown when the page doesn't fit on one row
+    pub menu_scroll: usize,
+    pub summary_click_regions: Vec<SummaryClickRegion>,
+    pub approve_click_regions: Vec<ApproveClickRegion>,
+    /// Waiting panes whose prompt got input (from the user or the ✓ / ✗
+    /// buttons) since the request; cleared by the pane's next hook event
+    pub answered_prompts: HashSet<u32>,
+    /// Last pane focused by clicking a summary count (for cycling)
+    pub summary_cursor: Option<u32>,
+    /// Local timezone offset from UTC, used by the clock
+    pub utc_offset_secs: i64,
+    /// Height of the plugin pane at the last render
+    pub rows: usize,
+    /// When each outstanding `set_timeout` is due, in ms
+    pub pending_ticks: Vec<u64>,
+    /// Settings given in the layout's plugin block
+    pub config_overrides: BTreeMap<String, String>,
     pub config_loaded: bool,
+    /// Host directory holding the settings file, once resolved
+    pub config_dir: Option<String>,
+    /// The host folder points at `config_dir`, so the file is read directly
+    pub config_on_host: bool,
+    /// Schema version of the settings file as read (newer files aren't overwritten)
+    pub config_version: u32,
+    /// Settings file contents as last read or written, to detect edits
+    pub config_raw: Option<String>,
+    /// Problem reading or writing the settings file
+    pub config_error: Option<String>,
+    pub last_config_poll: u64,
     pub hooks_installed: bool,
+    /// Protocol version of the last accepted hook payload
+    pub hook_protocol: Option<u32>,
+    /// Hook payloads that couldn't be used
+    pub rejected_payloads: u64,
+    /// Why the last hook payload was rejected
+    pub payload_error: Option<String>,
+    /// Show `payload_error` on the bar (until a payload is accepted again)
+    pub payload_error_active: bool,
+    /// Tabs scrolled off the left of the bar (`ScrollAction::ScrollTabs`)
+    pub tab_scroll: usize,
+    /// Most tabs the wheel may scroll off: as many as didn't fit on the bar
+    /// at the last render
+    pub tab_scroll_max: usize,
+    /// Tab and time (ms) of the last left click on a tab, to spot double clicks
+    pub last_tab_click: Option<(usize, u64)>,
+    /// Tab under the mouse and time (ms) of the last hover over it; its
+    /// sessions' details replace the tabs (or the detail row) for a moment
+    pub hover: Option<(usize, u64)>,
+    /// Tab segments as last drawn, standing in for them (hover and clicks)
+    /// while a tooltip covers the tabs
+    pub tab_regions: Vec<ClickRegion>,
+    /// Permission requests that arrived during do-not-disturb
+    pub dnd_suppressed: u32,
+    /// Stall notifications held back during do-not-disturb
+    pub dnd_stalls: u32,
+    /// Do-not-disturb was on at the last tick (to notice it ending)
+    pub dnd_was_active: bool,
+    /// This instance's plugin id
+    pub plugin_id: u32,
+    /// Panes whose title has looked like Claude's, to notice when it changes back
+    pub claude_titled: HashSet<u32>,
+    /// This instance acts for all of them where only one should (e.g. notifying)
+    pub is_leader: bool,
 }
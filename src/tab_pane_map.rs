@@ -26,3 +26,27 @@ pub fn build_pane_to_tab_map(
     }
     map
 }
+
+/// Whether `plugin_id` is the lowest-numbered pane running the same plugin,
+/// so exactly one instance handles work that shouldn't be duplicated.
+pub fn is_leader(manifest: &PaneManifest, plugin_id: u32) -> bool {
+    let plugins = || manifest.panes.values().flatten().filter(|p| p.is_plugin);
+    let Some(url) = plugins()
+        .find(|p| p.id == plugin_id)
+        .map(|p| p.plugin_url.clone())
+    else {
+        return false;
+    };
+    plugins()
+        .filter(|p| p.plugin_url == url)
+        .all(|p| p.id >= plugin_id)
+}
+
+/// Claude Code titles its pane `✳ <topic>` when idle and prefixes a braille
+/// spinner while working; a shell puts back its own title once it exits.
+pub fn is_claude_title(title: &str) -> bool {
+    title
+        .chars()
+        .next()
+        .is_some_and(|c| c == '✳' || ('\u{2800}'..='\u{28ff}').contains(&c))
+}